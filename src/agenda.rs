@@ -0,0 +1,134 @@
+//
+// The `agenda` command's plan of the coming days: a section per day holding the tasks whose date
+// key values overlap it, preceded by pending tasks whose `due:` date has passed and followed by
+// tasks with no value for any date key at all.  Tasks dated only beyond the last day are left out.
+//
+
+use crate::color::*;
+use crate::date::{Date, Duration};
+use crate::env::Env;
+use crate::error::*;
+use crate::field::*;
+use crate::month::Month;
+use crate::print::*;
+use crate::task::Task;
+
+// Arguments to `agenda`, each given as `name:value`.
+pub struct AgendaOptions<'a> {
+    days: usize,
+    keys: Vec<Key<'a>>,
+}
+
+pub struct Agenda;
+
+impl<'a> AgendaOptions<'a> {
+    const DAYS: &'static str = "days";
+    const KEYS: &'static str = "keys";
+
+    pub fn new(args: &[&'a str], date_keys: &[Key<'a>]) -> Result<Self> {
+        let mut options = AgendaOptions {
+            days: 7,
+            keys: date_keys.iter().map(|key| Key::new(key.as_str())).collect(),
+        };
+
+        for arg in args {
+            let (name, value) = match arg.split_once(':') {
+                Some((name, value)) => (name, value),
+                None => return Err(InvalidCommandArg(arg.to_string())),
+            };
+            match name {
+                AgendaOptions::DAYS => match value.parse::<usize>().ok().filter(|n| *n > 0) {
+                    Some(days) => options.days = days,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                AgendaOptions::KEYS => match Key::new_date_list(value, date_keys) {
+                    Some(keys) => options.keys = keys,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                _ => return Err(InvalidCommandArg(arg.to_string())),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl Agenda {
+    const OVERDUE: &'static str = "overdue";
+    const NO_DATE: &'static str = "no date";
+
+    const KEY_DUE: &'static str = "due";
+
+    pub fn print(
+        stdout: &mut String,
+        options: &AgendaOptions,
+        tasks: &[(Number, Task)],
+        date_keys: &[Key],
+        now: &Date,
+        env: &Env,
+    ) {
+        let today = now.first_day();
+        let dates = tasks
+            .iter()
+            .map(|(_, task)| {
+                options
+                    .keys
+                    .iter()
+                    .filter_map(|key| task.get_value(key).map(|value| (key, value)))
+                    .filter_map(|(key, value)| Date::from_abs(value.as_str()).map(|d| (key, d)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Sections in order, each with the indices of its tasks.
+        let mut sections: Vec<(String, Vec<usize>)> = Vec::new();
+        // Only a passed deadline makes a task overdue, not e.g. a `wait:` date which has ended.
+        let due = Key::new(Agenda::KEY_DUE);
+        let overdue = (0..tasks.len())
+            .filter(|i| !tasks[*i].1.is_completed())
+            .filter(|i| {
+                dates[*i]
+                    .iter()
+                    .any(|(key, date)| **key == due && date.before(&today))
+            })
+            .collect::<Vec<_>>();
+        sections.push((Agenda::OVERDUE.to_owned(), overdue));
+        let mut day = today;
+        for _ in 0..options.days {
+            let members = (0..tasks.len())
+                .filter(|i| dates[*i].iter().any(|(_, date)| date.overlaps(&day)))
+                .collect::<Vec<_>>();
+            sections.push((day.to_org(), members));
+            day += Duration::Days(1);
+        }
+        // Undated means without any date key, not merely without those chosen by `keys:`.
+        let undated = (0..tasks.len())
+            .filter(|i| {
+                date_keys
+                    .iter()
+                    .filter_map(|key| tasks[*i].1.get_value(key))
+                    .all(|value| Date::from_abs(value.as_str()).is_none())
+            })
+            .collect::<Vec<_>>();
+        sections.push((Agenda::NO_DATE.to_owned(), undated));
+
+        let max_nr_digits = sections
+            .iter()
+            .flat_map(|(_, members)| members.iter().map(|i| tasks[*i].0.digits()))
+            .max()
+            .unwrap_or(0);
+
+        for (heading, members) in sections {
+            Fg::Default.print(stdout, env.paint());
+            stdout.push_str(&heading);
+            stdout.push(' ');
+            stdout.push_str(&members.len().to_string());
+            stdout.push('\n');
+
+            for i in members {
+                Month::print_task(stdout, &tasks[i], max_nr_digits, env);
+            }
+        }
+        Fg::Default.print(stdout, env.paint());
+    }
+}
@@ -1,4 +1,5 @@
 use super::File;
+use crate::calendar::Calendar;
 use crate::command::Command;
 use crate::date::Date;
 use crate::error::*;
@@ -10,12 +11,14 @@ use crate::modification::Modification;
 enum ArgStage {
     Filter,
     Command,
+    CommandArg,
     Modification,
 }
 
 pub enum ArgNext<'a> {
     Filter(Filter<'a>),
     Command(Command),
+    CommandArg(&'a str),
     Modification(Modification<'a>),
 }
 
@@ -23,21 +26,33 @@ pub struct ArgIter<'a> {
     stage: ArgStage,
     stack: Vec<&'a str>,
     force_append: bool,
+    // The report being expanded, and how many arguments remain beneath its contents
+    report: Option<(&'a str, usize)>,
     now: &'a Date,
+    // The current time relative to which modifications are written
+    written_now: &'a Date,
     date_keys: &'a [Key<'a>],
+    calendar: &'a Calendar,
     filter_aliases: &'a [File],
     command_aliases: &'a [File],
     modification_aliases: &'a [File],
+    reports: &'a [File],
 }
 
 impl<'a> ArgIter<'a> {
+    const REPORT_COMMENT: char = '#';
+
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         args: &'a [String],
         now: &'a Date,
+        written_now: &'a Date,
         date_keys: &'a [Key<'a>],
+        calendar: &'a Calendar,
         filter_aliases: &'a [File],
         command_aliases: &'a [File],
         modification_aliases: &'a [File],
+        reports: &'a [File],
     ) -> Self {
         let mut stack = Vec::new();
         for arg in args {
@@ -48,11 +63,15 @@ impl<'a> ArgIter<'a> {
             stage: ArgStage::Filter,
             stack,
             force_append: false,
+            report: None,
             now,
+            written_now,
             date_keys,
+            calendar,
             filter_aliases,
             command_aliases,
             modification_aliases,
+            reports,
         }
     }
 }
@@ -79,14 +98,44 @@ impl<'a> Iterator for ArgIter<'a> {
             arg = self.stack.pop()?;
         }
 
+        // A report must name its command before any arguments following it on the CLI.
+        if let (ArgStage::Filter, Some((name, rest))) = (&self.stage, self.report) {
+            if self.stack.len() < rest {
+                return Some(Err(ReportExpectsListCommand(name.to_owned())));
+            }
+        }
+
         if self.stage == ArgStage::Filter {
-            match Filter::new(arg, &self.now, &self.date_keys) {
+            match Filter::new(arg, self.now, self.date_keys, self.calendar) {
                 Err(err) => return Some(Err(err)),
                 Ok(Some(filter)) => return Some(Ok(ArgNext::Filter(filter))),
                 Ok(None) => self.stage = ArgStage::Command,
             }
         }
 
+        // Built-in commands and command aliases take precedence over reports of the same name.
+        if self.stage == ArgStage::Command
+            && self.report.is_none()
+            && Command::new(arg).is_none()
+            && find_name(arg, self.command_aliases).is_none()
+        {
+            if let Some(File { name, content }) = find_name(arg, self.reports) {
+                let i = self.stack.len();
+                let lines = content
+                    .lines()
+                    .filter(|line| !line.trim_start().starts_with(ArgIter::REPORT_COMMENT));
+                for mut line in lines {
+                    while let Some((head, tail)) = split_token(line) {
+                        self.stack.insert(i, head);
+                        line = tail;
+                    }
+                }
+                self.stage = ArgStage::Filter;
+                self.report = Some((name, i));
+                return self.next();
+            }
+        }
+
         if self.stage == ArgStage::Command {
             if let Some(File { content, .. }) = find_name(arg, self.command_aliases) {
                 let mut aliases = content.as_ref();
@@ -100,13 +149,41 @@ impl<'a> Iterator for ArgIter<'a> {
         }
 
         if self.stage == ArgStage::Command {
-            self.stage = ArgStage::Modification;
             return match Command::new(arg) {
-                Some(command) => Some(Ok(ArgNext::Command(command))),
+                Some(command) if command.writes_tasks() && self.report.is_some() => {
+                    let (name, _) = self.report?;
+                    Some(Err(ReportExpectsListCommand(name.to_owned())))
+                }
+                Some(command) => {
+                    self.stage = match command.takes_args() {
+                        true => ArgStage::CommandArg,
+                        false => ArgStage::Modification,
+                    };
+                    Some(Ok(ArgNext::Command(command)))
+                }
                 None => Some(Err(NotAFilterOrCommand(arg.to_owned()))),
             };
         }
 
+        if self.stage == ArgStage::CommandArg {
+            while is_all_whitespace(arg) {
+                arg = self.stack.pop()?;
+            }
+            if arg.contains(|c: char| c.is_ascii_whitespace()) {
+                let mut content = arg;
+                let i = self.stack.len();
+                while let Some((head, tail)) = split_token(content) {
+                    self.stack.insert(i, head);
+                    content = tail;
+                }
+                arg = self.stack.pop()?;
+                while is_all_whitespace(arg) {
+                    arg = self.stack.pop()?;
+                }
+            }
+            return Some(Ok(ArgNext::CommandArg(arg)));
+        }
+
         if let Some(File { content, .. }) = find_name(arg, self.modification_aliases) {
             let mut aliases = content.as_ref();
             let i = self.stack.len();
@@ -133,7 +210,7 @@ impl<'a> Iterator for ArgIter<'a> {
             arg = self.stack.pop()?;
         }
 
-        Some(match Modification::new(arg, &self.now, &self.date_keys) {
+        Some(match Modification::new(arg, self.written_now, self.date_keys, self.calendar) {
             Ok(Modification::Append(str)) => {
                 self.force_append = true;
                 Ok(ArgNext::Modification(Modification::Append(str)))
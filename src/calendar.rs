@@ -0,0 +1,151 @@
+//
+// Which days count as business days when offsetting by week days (`W`).  Read from
+// `~/.chore/weekend` and the files in `~/.chore/holidays`, and passed to wherever dates are parsed.
+//
+
+use crate::error::*;
+use crate::File;
+use chrono::Datelike;
+
+pub struct Calendar {
+    weekend: Vec<chrono::Weekday>,
+    holidays: Vec<Holiday>,
+}
+
+enum Holiday {
+    // Inclusive range of dates
+    Range(chrono::NaiveDate, chrono::NaiveDate),
+    // Month and day, every year
+    Annual(u32, u32),
+}
+
+impl Default for Calendar {
+    fn default() -> Calendar {
+        Calendar {
+            weekend: vec![chrono::Weekday::Sat, chrono::Weekday::Sun],
+            holidays: Vec::new(),
+        }
+    }
+}
+
+impl Calendar {
+    const RANGE: &'static str = "..";
+    const COMMENT: char = '#';
+
+    // Week day offsets may reach at most this many days away, in place of searching endlessly
+    // when holidays cover every day.
+    const MAX_DAYS: i64 = 100 * 366;
+
+    pub fn new(weekend: Option<&str>, holidays: &[File]) -> Result<Calendar> {
+        let mut calendar = Calendar::default();
+
+        if let Some(weekend) = weekend.filter(|str| !str.trim().is_empty()) {
+            calendar.weekend = Vec::new();
+            for str in weekend.split_whitespace() {
+                match str.parse::<chrono::Weekday>() {
+                    Ok(day) => calendar.weekend.push(day),
+                    Err(_) => return Err(InvalidWeekday(str.to_owned())),
+                }
+            }
+            calendar
+                .weekend
+                .sort_by_key(|day| day.num_days_from_monday());
+            calendar.weekend.dedup();
+            // Every day being a weekend would leave no business day to offset to.
+            if calendar.weekend.len() == 7 {
+                return Err(InvalidWeekend(weekend.trim().to_owned()));
+            }
+        }
+
+        for File { content, .. } in holidays {
+            for line in content.lines() {
+                let line = line.split(Calendar::COMMENT).next().unwrap_or("").trim();
+                if line.is_empty() {
+                    continue;
+                }
+                match Holiday::new(line) {
+                    Some(holiday) => calendar.holidays.push(holiday),
+                    None => return Err(InvalidHoliday(line.to_owned())),
+                }
+            }
+        }
+
+        Ok(calendar)
+    }
+
+    pub fn is_business_day(&self, date: &chrono::NaiveDate) -> bool {
+        !self.weekend.contains(&date.weekday()) && !self.holidays.iter().any(|h| h.contains(date))
+    }
+
+    // Number of calendar days from `from` to the date `count` business days away, if within
+    // reach.
+    pub fn business_days(&self, from: &chrono::NaiveDate, count: i64) -> Option<i64> {
+        let per_week = 7 - self.weekend.len() as i64;
+        if count.abs() / per_week * 7 > Calendar::MAX_DAYS {
+            return None;
+        }
+        let day = |days: i64| *from + chrono::Duration::days(days);
+
+        let step = count.signum();
+        let mut days = 0;
+        let mut remaining = count.abs();
+        while remaining > 0 {
+            // Every whole week holds the same business days but for holidays, which are made up
+            // for afterwards.
+            let weeks = (remaining - 1) / per_week;
+            match weeks {
+                0 => {
+                    days += step;
+                    if self.is_business_day(&day(days)) {
+                        remaining -= 1;
+                    }
+                }
+                _ => {
+                    let span = (1..=weeks * 7).map(|i| days + step * i);
+                    let missed = match self.holidays.is_empty() {
+                        true => 0,
+                        false => span
+                            .map(day)
+                            .filter(|date| !self.weekend.contains(&date.weekday()))
+                            .filter(|date| !self.is_business_day(date))
+                            .count() as i64,
+                    };
+                    days += step * weeks * 7;
+                    remaining -= weeks * per_week - missed;
+                }
+            }
+            if days.abs() > Calendar::MAX_DAYS {
+                return None;
+            }
+        }
+        Some(days)
+    }
+}
+
+impl Holiday {
+    fn new(str: &str) -> Option<Holiday> {
+        let parse = |str: &str| chrono::NaiveDate::parse_from_str(str.trim(), "%Y-%m-%d").ok();
+
+        if let Some((start, end)) = str.split_once(Calendar::RANGE) {
+            let (start, end) = (parse(start)?, parse(end)?);
+            return match start <= end {
+                true => Some(Holiday::Range(start, end)),
+                false => Some(Holiday::Range(end, start)),
+            };
+        }
+        if let Some(date) = parse(str) {
+            return Some(Holiday::Range(date, date));
+        }
+
+        // Validate against a leap year so that 02-29 is accepted.
+        let date = chrono::NaiveDate::parse_from_str(&format!("2000-{}", str), "%Y-%m-%d").ok()?;
+        Some(Holiday::Annual(date.month(), date.day()))
+    }
+
+    fn contains(&self, date: &chrono::NaiveDate) -> bool {
+        match self {
+            Holiday::Range(start, end) => start <= date && date <= end,
+            Holiday::Annual(month, day) => date.month() == *month && date.day() == *day,
+        }
+    }
+}
@@ -1,14 +1,16 @@
+use crate::error::*;
 use std::cell::Cell;
 
 thread_local! {
-    static CURRENT_FG: Cell<Fg> = Cell::new(Fg::Default);
-    static CURRENT_BG: Cell<Bg> = Cell::new(Bg::Default);
+    static CURRENT_FG: Cell<Fg> = const { Cell::new(Fg::Default) };
+    static CURRENT_BG: Cell<Bg> = const { Cell::new(Bg::Default) };
 }
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Bg {
     Default,
     Custom(u8),
+    Rgb(u8, u8, u8),
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -23,9 +25,52 @@ pub enum Fg {
     Red,
     White,
     Yellow,
+    Custom(u8),
+    Rgb(u8, u8, u8),
+}
+
+// A colour as given, either from the 256-colour palette or as red, green and blue.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Shade {
+    Code(u8),
+    Rgb(u8, u8, u8),
+}
+
+// How many colours the terminal can show.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Depth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+// Whether to print colour at all.
+#[derive(Clone, Copy, PartialEq)]
+pub enum ColorChoice {
+    Always,
+    Never,
+    Auto,
 }
 
 impl Bg {
+    // A colour by name, or from the 256-colour palette by number.
+    pub fn new(str: &str) -> Option<Self> {
+        match Fg::new(str)?.shade() {
+            None => Some(Bg::Default),
+            Some(Shade::Code(code)) => Some(Bg::Custom(code)),
+            Some(Shade::Rgb(r, g, b)) => Some(Bg::Rgb(r, g, b)),
+        }
+    }
+
+    // None for the terminal's default.
+    pub fn shade(&self) -> Option<Shade> {
+        match self {
+            Bg::Default => None,
+            Bg::Custom(code) => Some(Shade::Code(*code)),
+            Bg::Rgb(r, g, b) => Some(Shade::Rgb(*r, *g, *b)),
+        }
+    }
+
     pub fn get() -> Self {
         CURRENT_BG.with(|current| current.get())
     }
@@ -36,6 +81,64 @@ impl Bg {
 }
 
 impl Fg {
+    const BLACK: &'static str = "black";
+    const BLUE: &'static str = "blue";
+    const CYAN: &'static str = "cyan";
+    const DEFAULT: &'static str = "default";
+    const GREEN: &'static str = "green";
+    const GRAY: &'static str = "gray";
+    const MAGENTA: &'static str = "magenta";
+    const RED: &'static str = "red";
+    const WHITE: &'static str = "white";
+    const YELLOW: &'static str = "yellow";
+
+    const RGB_PREFIX: char = '#';
+
+    // A colour by name, from the 256-colour palette by number, or as `#rrggbb`.
+    pub fn new(str: &str) -> Option<Self> {
+        if let Some(hex) = str.strip_prefix(Fg::RGB_PREFIX) {
+            let channel = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|c| u8::from_str_radix(c, 16).ok())
+            };
+            return match hex.len() {
+                6 => Some(Fg::Rgb(channel(0)?, channel(2)?, channel(4)?)),
+                _ => None,
+            };
+        }
+        match str {
+            Fg::BLACK => Some(Fg::Black),
+            Fg::BLUE => Some(Fg::Blue),
+            Fg::CYAN => Some(Fg::Cyan),
+            Fg::DEFAULT => Some(Fg::Default),
+            Fg::GREEN => Some(Fg::Green),
+            Fg::GRAY => Some(Fg::LightGray),
+            Fg::MAGENTA => Some(Fg::Magenta),
+            Fg::RED => Some(Fg::Red),
+            Fg::WHITE => Some(Fg::White),
+            Fg::YELLOW => Some(Fg::Yellow),
+            _ => str.parse::<u8>().ok().map(Fg::Custom),
+        }
+    }
+
+    // None for the terminal's default.
+    pub fn shade(&self) -> Option<Shade> {
+        match self {
+            Fg::Black => Some(Shade::Code(0)),
+            Fg::Blue => Some(Shade::Code(33)),
+            Fg::Cyan => Some(Shade::Code(6)),
+            Fg::Green => Some(Shade::Code(2)),
+            Fg::LightGray => Some(Shade::Code(246)),
+            Fg::Magenta => Some(Shade::Code(92)),
+            Fg::Red => Some(Shade::Code(196)),
+            Fg::White => Some(Shade::Code(255)),
+            Fg::Yellow => Some(Shade::Code(11)),
+            Fg::Custom(code) => Some(Shade::Code(*code)),
+            Fg::Rgb(r, g, b) => Some(Shade::Rgb(*r, *g, *b)),
+            Fg::Default => None,
+        }
+    }
+
     pub fn get() -> Self {
         CURRENT_FG.with(|current| current.get())
     }
@@ -44,3 +147,173 @@ impl Fg {
         CURRENT_FG.with(|current| current.set(*self))
     }
 }
+
+impl Shade {
+    // xterm's default values for the first sixteen colours of the palette.
+    const SYSTEM: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    // Channel values of the 6x6x6 colour cube making up codes 16 to 231
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Shade::Rgb(r, g, b) => (r, g, b),
+            Shade::Code(code @ 0..=15) => Shade::SYSTEM[code as usize],
+            Shade::Code(code @ 16..=231) => {
+                let i = code as usize - 16;
+                (
+                    Shade::CUBE[i / 36],
+                    Shade::CUBE[i / 6 % 6],
+                    Shade::CUBE[i % 6],
+                )
+            }
+            Shade::Code(code) => {
+                let level = 8 + 10 * (code - 232);
+                (level, level, level)
+            }
+        }
+    }
+
+    fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+        let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+        d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+    }
+
+    // The nearest of the codes in the range, by their red, green and blue.
+    fn nearest(&self, codes: std::ops::RangeInclusive<u8>) -> u8 {
+        let rgb = self.rgb();
+        codes
+            .min_by_key(|code| Shade::distance(rgb, Shade::Code(*code).rgb()))
+            .unwrap_or(0)
+    }
+}
+
+impl Depth {
+    const ANSI16: &'static str = "16";
+    const ANSI256: &'static str = "256";
+    const TRUECOLOR: &'static str = "truecolor";
+
+    pub fn new(str: &str) -> Option<Self> {
+        match str {
+            Depth::ANSI16 => Some(Depth::Ansi16),
+            Depth::ANSI256 => Some(Depth::Ansi256),
+            Depth::TRUECOLOR => Some(Depth::TrueColor),
+            _ => None,
+        }
+    }
+
+    // The depth a terminal advertises through `COLORTERM`, else the 256 colours most support.
+    pub fn detect(colorterm: Option<&str>) -> Self {
+        match colorterm {
+            Some("truecolor") | Some("24bit") => Depth::TrueColor,
+            _ => Depth::Ansi256,
+        }
+    }
+
+    // The parameters of the escape sequence selecting the shade, e.g. `38;5;196`, approximating
+    // it where the terminal has too few colours.
+    pub fn sgr(&self, shade: Shade, background: bool) -> String {
+        let layer = match background {
+            true => 48,
+            false => 38,
+        };
+        match (self, shade) {
+            (Depth::Ansi16, shade) => {
+                let code = shade.nearest(0..=15);
+                let base = match (background, code < 8) {
+                    (false, true) => 30,
+                    (false, false) => 90 - 8,
+                    (true, true) => 40,
+                    (true, false) => 100 - 8,
+                };
+                (base + code).to_string()
+            }
+            (Depth::Ansi256, Shade::Code(code)) => format!("{};5;{}", layer, code),
+            (Depth::Ansi256, shade) => format!("{};5;{}", layer, shade.nearest(16..=255)),
+            (Depth::TrueColor, shade) => {
+                let (r, g, b) = shade.rgb();
+                format!("{};2;{};{};{}", layer, r, g, b)
+            }
+        }
+    }
+}
+
+impl ColorChoice {
+    const COLOR_OPTION: &'static str = "--color=";
+    const DEPTH_OPTION: &'static str = "--color-depth=";
+
+    const ALWAYS: &'static str = "always";
+    const NEVER: &'static str = "never";
+    const AUTO: &'static str = "auto";
+
+    pub fn new(str: &str) -> Option<Self> {
+        match str {
+            ColorChoice::ALWAYS => Some(ColorChoice::Always),
+            ColorChoice::NEVER => Some(ColorChoice::Never),
+            ColorChoice::AUTO => Some(ColorChoice::Auto),
+            _ => None,
+        }
+    }
+}
+
+// Whether and how deeply to colour output, from options leading the arguments, e.g.
+// `--color=never`, then from the environment's conventions.
+pub struct ColorConfig {
+    pub print_color: bool,
+    pub depth: Depth,
+}
+
+impl ColorConfig {
+    const DUMB_TERM: &'static str = "dumb";
+
+    // The colouring and the arguments following the options.
+    pub fn new<'a>(
+        args: &'a [String],
+        is_terminal: bool,
+        no_color: bool,
+        clicolor_force: bool,
+        term: Option<&str>,
+        colorterm: Option<&str>,
+    ) -> Result<(Self, &'a [String])> {
+        let mut choice = ColorChoice::Auto;
+        let mut depth = None;
+        let mut rest = args;
+        while let Some(arg) = rest.first() {
+            let invalid = || InvalidOption(arg.to_owned());
+            if let Some(value) = arg.strip_prefix(ColorChoice::COLOR_OPTION) {
+                choice = ColorChoice::new(value).ok_or_else(invalid)?;
+            } else if let Some(value) = arg.strip_prefix(ColorChoice::DEPTH_OPTION) {
+                depth = Some(Depth::new(value).ok_or_else(invalid)?);
+            } else {
+                break;
+            }
+            rest = &rest[1..];
+        }
+
+        let print_color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto if no_color => false,
+            ColorChoice::Auto if clicolor_force => true,
+            ColorChoice::Auto => is_terminal && term != Some(ColorConfig::DUMB_TERM),
+        };
+        let depth = depth.unwrap_or_else(|| Depth::detect(colorterm));
+        Ok((ColorConfig { print_color, depth }, rest))
+    }
+}
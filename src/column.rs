@@ -0,0 +1,236 @@
+// A single piece of information extracted from each task, used when rendering tasks as fields
+// rather than as raw task lines.
+
+use crate::date::Date;
+use crate::error::*;
+use crate::field::*;
+use crate::task::Task;
+use crate::theme::Part;
+use crate::token::Token;
+
+pub struct Column<'a> {
+    name: &'a str,
+    kind: Kind<'a>,
+    date_style: DateStyle,
+}
+
+enum Kind<'a> {
+    Number,
+    Done,
+    End,
+    Priority,
+    Entry,
+    Age,
+    Body,
+    Projects,
+    Contexts,
+    Annotations,
+    Value(Key<'a>),
+}
+
+// How date values are rendered.
+enum DateStyle {
+    // As written in the task
+    Stored,
+    // As a full `YYYY-MM-DDTHH:MM:SS` timestamp
+    Full,
+    // As a span from now, e.g. `3d` or `-2w`
+    Relative,
+}
+
+impl<'a> Column<'a> {
+    const NR: &'static str = "nr";
+    const DONE: &'static str = "done";
+    const END: &'static str = "end";
+    const PRI: &'static str = "pri";
+    const ENTRY: &'static str = "entry";
+    const AGE: &'static str = "age";
+    const BODY: &'static str = "body";
+    const PROJECTS: &'static str = "projects";
+    const CONTEXTS: &'static str = "contexts";
+    const ANNOTATIONS: &'static str = "annotations";
+
+    const MOD_FULL: &'static str = "full";
+    const MOD_REL: &'static str = "rel";
+
+    const KEY_DUE: &'static str = "due";
+
+    pub fn new(str: &'a str, date_keys: &[Key]) -> Result<Self> {
+        let (name, is_key) = match str.strip_suffix(':') {
+            Some(name) => (name, true),
+            None => (str, false),
+        };
+        let (name, xmod) = match name.split_once('.') {
+            Some((name, xmod)) => (name, Some(xmod)),
+            None => (name, None),
+        };
+
+        let kind = match (name, is_key) {
+            (Column::NR, false) => Kind::Number,
+            (Column::DONE, false) => Kind::Done,
+            (Column::END, false) => Kind::End,
+            (Column::PRI, false) => Kind::Priority,
+            (Column::ENTRY, false) => Kind::Entry,
+            (Column::AGE, false) => Kind::Age,
+            (Column::BODY, false) => Kind::Body,
+            (Column::PROJECTS, false) => Kind::Projects,
+            (Column::CONTEXTS, false) => Kind::Contexts,
+            (Column::ANNOTATIONS, false) => Kind::Annotations,
+            (_, true) if !name.is_empty() => Kind::Value(Key::new(name)),
+            _ => return Err(InvalidColumn(str.to_owned())),
+        };
+
+        let is_date = match &kind {
+            Kind::End | Kind::Entry => true,
+            Kind::Value(key) => date_keys.contains(key),
+            _ => false,
+        };
+        let date_style = match (xmod, is_date) {
+            (None, _) => DateStyle::Stored,
+            (Some(Column::MOD_FULL), true) => DateStyle::Full,
+            (Some(Column::MOD_REL), true) => DateStyle::Relative,
+            (Some(Column::MOD_FULL), false) | (Some(Column::MOD_REL), false) => {
+                return Err(ModExpectsDateKey(str.to_owned()))
+            }
+            (Some(_), _) => return Err(InvalidMod(str.to_owned())),
+        };
+
+        Ok(Column {
+            name,
+            kind,
+            date_style,
+        })
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            (Column::NR, Kind::Number),
+            (Column::DONE, Kind::Done),
+            (Column::END, Kind::End),
+            (Column::PRI, Kind::Priority),
+            (Column::ENTRY, Kind::Entry),
+            (Column::BODY, Kind::Body),
+        ]
+        .into_iter()
+        .map(|(name, kind)| Column {
+            name,
+            kind,
+            date_style: DateStyle::Stored,
+        })
+        .collect()
+    }
+
+    // Columns of the default table: due relative to now only if `due` is a date key.
+    pub fn table_defaults(date_keys: &[Key]) -> Vec<Self> {
+        let due_style = match date_keys.contains(&Key::new(Column::KEY_DUE)) {
+            true => DateStyle::Relative,
+            false => DateStyle::Stored,
+        };
+        vec![
+            (Column::NR, Kind::Number, DateStyle::Stored),
+            (Column::PRI, Kind::Priority, DateStyle::Stored),
+            (
+                Column::KEY_DUE,
+                Kind::Value(Key::new(Column::KEY_DUE)),
+                due_style,
+            ),
+            (Column::AGE, Kind::Age, DateStyle::Stored),
+            (Column::PROJECTS, Kind::Projects, DateStyle::Stored),
+            (Column::BODY, Kind::Body, DateStyle::Stored),
+        ]
+        .into_iter()
+        .map(|(name, kind, date_style)| Column {
+            name,
+            kind,
+            date_style,
+        })
+        .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    // Whether the column's text may be wrapped to fit narrow terminals.
+    pub fn is_body(&self) -> bool {
+        matches!(self.kind, Kind::Body)
+    }
+
+    // Whether the column's cells line up on their right edge.
+    pub fn is_right_aligned(&self) -> bool {
+        matches!(self.kind, Kind::Number)
+    }
+
+    // The part of the theme whose colour the column's cells are printed in.
+    pub fn part(&self) -> Part {
+        match self.kind {
+            Kind::Number => Part::Number,
+            Kind::Done => Part::Marker,
+            Kind::End => Part::End,
+            Kind::Entry | Kind::Age => Part::Entry,
+            Kind::Priority | Kind::Body => Part::Normal,
+            Kind::Projects => Part::Project,
+            Kind::Contexts => Part::Context,
+            Kind::Annotations => Part::Annotation,
+            Kind::Value(_) => Part::Value,
+        }
+    }
+
+    pub fn render(&self, task: &Task, nr: Number, now: &Date) -> String {
+        match &self.kind {
+            Kind::Number => nr.as_usize().to_string(),
+            Kind::Done => match task.is_completed() {
+                true => "x".to_owned(),
+                false => String::new(),
+            },
+            Kind::End => match task.get_end() {
+                Some(end) => self.render_date(end.as_str(), now),
+                None => String::new(),
+            },
+            Kind::Priority => match task.get_priority() {
+                Some(pri) => (pri.as_u8() as char).to_string(),
+                None => String::new(),
+            },
+            Kind::Entry => match task.get_entry() {
+                Some(entry) => self.render_date(entry.as_str(), now),
+                None => String::new(),
+            },
+            Kind::Age => match task.get_entry().and_then(|e| Date::from_abs(e.as_str())) {
+                Some(entry) => Date::span(entry.days_from(now)),
+                None => String::new(),
+            },
+            Kind::Body => task.get_body(),
+            Kind::Projects => task
+                .iter()
+                .filter(|(token, _)| matches!(token, Token::Project(_)))
+                .map(|(_, range)| &task.as_str()[range])
+                .collect::<Vec<_>>()
+                .join(" "),
+            Kind::Contexts => task
+                .iter()
+                .filter(|(token, _)| matches!(token, Token::Context(_)))
+                .map(|(_, range)| &task.as_str()[range])
+                .collect::<Vec<_>>()
+                .join(" "),
+            Kind::Annotations => task.get_annotations().join(" | "),
+            Kind::Value(key) => match task.get_value(key) {
+                Some(value) => self.render_date(value.as_str(), now),
+                None => String::new(),
+            },
+        }
+    }
+
+    fn render_date(&self, str: &str, now: &Date) -> String {
+        match (Date::from_abs(str), &self.date_style) {
+            (Some(date), DateStyle::Full) => date.to_timestamp(),
+            (Some(date), DateStyle::Relative) => {
+                let days = date.days_from(now);
+                match days < 0.0 {
+                    true => format!("-{}", Date::span(days)),
+                    false => Date::span(days),
+                }
+            }
+            _ => str.to_owned(),
+        }
+    }
+}
@@ -1,36 +1,188 @@
+use crate::agenda::{Agenda, AgendaOptions};
 use crate::color::*;
+use crate::column::Column;
+use crate::date::Date;
+use crate::depends::Dependencies;
+use crate::env::Env;
 use crate::error::*;
+use crate::export::{Checklist, ChecklistOptions, Format};
 use crate::field::*;
 use crate::filter::Filter;
+use crate::group::Group;
+use crate::humanize::DateDisplay;
 use crate::modification::{ModOutput, Modification};
+use crate::month::{CalendarOptions, Month};
 use crate::print::*;
+use crate::sort::SortKey;
+use crate::table::{Overflow, Table};
+use crate::tags::VirtualTag;
 use crate::task::{Task, TaskBuf};
+use crate::theme::Paint;
 use crate::token::Token;
-use crate::Output;
-use std::collections::HashSet;
+use crate::{File, Output};
+use std::collections::{BTreeMap, HashSet};
 
 pub enum Command {
     ListTasks,
+    ListNext,
     ListProjects,
     ListContexts,
     ListKeys,
+    ListTags,
+    ListReports,
+    Calendar,
+    Agenda,
+    ExportCsv,
+    ExportTsv,
+    ExportMarkdown,
+    ExportOrg,
     AddTask,
     RemoveTasks,
     ModifyTasks,
     Undo,
 }
 
+// Arguments to `list`, each given as `name:value`.
+struct ListOptions<'a> {
+    annotations: AnnotationStyle,
+    sort: Vec<SortKey>,
+    group: Option<Group>,
+    limit: Option<usize>,
+    // Shown as a table of these columns rather than as task lines, if any
+    columns: Vec<Column<'a>>,
+    overflow: Overflow,
+    dates: DateDisplay,
+}
+
+// How `list` prints each task line.
+struct LineStyle<'a> {
+    annotations: &'a AnnotationStyle,
+    dates: DateDisplay,
+    now: &'a Date,
+    date_keys: &'a [Key<'a>],
+}
+
+// How `list` shows the annotations following a task's body.
+enum AnnotationStyle {
+    Inline,
+    Indent,
+    Hide,
+}
+
 enum TokenType {
     Projects,
     Contexts,
     Keys,
 }
 
+impl<'a> ListOptions<'a> {
+    const ANNOTATIONS: &'static str = "annotations";
+    const SORT: &'static str = "sort";
+    const GROUP: &'static str = "group";
+    const LIMIT: &'static str = "limit";
+    const COLUMNS: &'static str = "columns";
+    const OVERFLOW: &'static str = "overflow";
+    const DATES: &'static str = "dates";
+
+    const COLUMN_SEPARATOR: char = ',';
+    const DEFAULT_COLUMNS: &'static str = "default";
+
+    // Tasks are indented this far beneath their group's heading.
+    const GROUP_INDENT: usize = 2;
+
+    fn new(args: &[&'a str], date_keys: &[Key]) -> Result<Self> {
+        let mut options = ListOptions {
+            annotations: AnnotationStyle::Inline,
+            sort: Vec::new(),
+            group: None,
+            limit: None,
+            columns: Vec::new(),
+            overflow: Overflow::Wrap,
+            dates: DateDisplay::Stored,
+        };
+
+        for arg in args {
+            let (name, value) = match arg.split_once(':') {
+                Some((name, value)) => (name, value),
+                None => return Err(InvalidCommandArg(arg.to_string())),
+            };
+            match name {
+                ListOptions::ANNOTATIONS => match AnnotationStyle::new(value) {
+                    Some(style) => options.annotations = style,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                ListOptions::SORT => match SortKey::new_list(value, date_keys) {
+                    Some(keys) => options.sort = keys,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                ListOptions::GROUP => match Group::new(value, date_keys) {
+                    Some(group) => options.group = Some(group),
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                ListOptions::LIMIT => match value.parse::<usize>().ok().filter(|n| *n > 0) {
+                    Some(limit) => options.limit = Some(limit),
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                ListOptions::COLUMNS => match value {
+                    "" => return Err(InvalidCommandArg(arg.to_string())),
+                    ListOptions::DEFAULT_COLUMNS => {
+                        options.columns = Column::table_defaults(date_keys)
+                    }
+                    _ => {
+                        options.columns = value
+                            .split(ListOptions::COLUMN_SEPARATOR)
+                            .map(|column| Column::new(column, date_keys))
+                            .collect::<Result<Vec<_>>>()?
+                    }
+                },
+                ListOptions::OVERFLOW => match Overflow::new(value) {
+                    Some(overflow) => options.overflow = overflow,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                ListOptions::DATES => match DateDisplay::new(value) {
+                    Some(dates) => options.dates = dates,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                _ => return Err(InvalidCommandArg(arg.to_string())),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl AnnotationStyle {
+    const INLINE: &'static str = "inline";
+    const INDENT: &'static str = "indent";
+    const HIDE: &'static str = "hide";
+
+    // Annotations are indented this far past the start of their task's text.
+    const INDENT_WIDTH: usize = 2;
+
+    fn new(str: &str) -> Option<Self> {
+        match str {
+            AnnotationStyle::INLINE => Some(AnnotationStyle::Inline),
+            AnnotationStyle::INDENT => Some(AnnotationStyle::Indent),
+            AnnotationStyle::HIDE => Some(AnnotationStyle::Hide),
+            _ => None,
+        }
+    }
+}
+
 impl<'a> Command {
     const LIST: &'static str = "list";
+    const NEXT: &'static str = "next";
     const PROJECTS: &'static str = "projects";
     const CONTEXTS: &'static str = "contexts";
     const KEYS: &'static str = "keys";
+    const TAGS: &'static str = "tags";
+    const REPORTS: &'static str = "reports";
+    const CALENDAR: &'static str = "calendar";
+    const AGENDA: &'static str = "agenda";
+    const CSV: &'static str = "csv";
+    const TSV: &'static str = "tsv";
+    const MARKDOWN: &'static str = "markdown";
+    const ORG: &'static str = "org";
     const ADD: &'static str = "add";
     const DELETE: &'static str = "delete";
     const MODIFY: &'static str = "modify";
@@ -39,9 +191,18 @@ impl<'a> Command {
     pub fn new(str: &'a str) -> Option<Self> {
         match str {
             Command::LIST => Some(Command::ListTasks),
+            Command::NEXT => Some(Command::ListNext),
             Command::PROJECTS => Some(Command::ListProjects),
             Command::CONTEXTS => Some(Command::ListContexts),
             Command::KEYS => Some(Command::ListKeys),
+            Command::TAGS => Some(Command::ListTags),
+            Command::REPORTS => Some(Command::ListReports),
+            Command::CALENDAR => Some(Command::Calendar),
+            Command::AGENDA => Some(Command::Agenda),
+            Command::CSV => Some(Command::ExportCsv),
+            Command::TSV => Some(Command::ExportTsv),
+            Command::MARKDOWN => Some(Command::ExportMarkdown),
+            Command::ORG => Some(Command::ExportOrg),
             Command::ADD => Some(Command::AddTask),
             Command::DELETE => Some(Command::RemoveTasks),
             Command::MODIFY => Some(Command::ModifyTasks),
@@ -50,99 +211,439 @@ impl<'a> Command {
         }
     }
 
+    // Whether the command takes its own arguments in place of modifications.
+    pub fn takes_args(&self) -> bool {
+        matches!(
+            self,
+            Command::ListTasks
+                | Command::Calendar
+                | Command::Agenda
+                | Command::ExportCsv
+                | Command::ExportTsv
+                | Command::ExportMarkdown
+                | Command::ExportOrg
+        )
+    }
+
+    // Whether the command changes the task file, which reports may not do.
+    pub fn writes_tasks(&self) -> bool {
+        matches!(
+            self,
+            Command::AddTask | Command::RemoveTasks | Command::ModifyTasks | Command::Undo
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn run(
         &self,
         tasks: String,
         undo: String,
         filters: &[Filter],
         mods: &[Modification],
+        args: &[&str],
         date_keys: &'a [Key<'a>],
-        print_color: bool,
+        reports: &[File],
+        now: &Date,
+        width: Option<usize>,
+        env: &Env,
     ) -> Result<Output> {
         match self {
             Command::ListTasks => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::list_tasks(tasks, filters, print_color)
+                Command::list_tasks(tasks, filters, args, date_keys, now, width, env)
+            }
+            Command::ListNext => {
+                if !mods.is_empty() {
+                    return Err(CmdDisallowsMod);
+                }
+                Command::list_next(tasks, filters, env)
             }
             Command::ListProjects => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::list_tokens(tasks, filters, TokenType::Projects, print_color)
+                Command::list_token_tree(tasks, filters, TokenType::Projects, env)
             }
             Command::ListContexts => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::list_tokens(tasks, filters, TokenType::Contexts, print_color)
+                Command::list_token_tree(tasks, filters, TokenType::Contexts, env)
             }
             Command::ListKeys => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::list_tokens(tasks, filters, TokenType::Keys, print_color)
+                Command::list_tokens(tasks, filters, TokenType::Keys, env)
+            }
+            Command::ListTags => {
+                if !mods.is_empty() {
+                    return Err(CmdDisallowsMod);
+                }
+                Command::list_virtual_tags(tasks, filters, env)
+            }
+            Command::ListReports => {
+                if !mods.is_empty() {
+                    return Err(CmdDisallowsMod);
+                }
+                Command::list_reports(reports, env)
+            }
+            Command::Calendar => {
+                if !mods.is_empty() {
+                    return Err(CmdDisallowsMod);
+                }
+                Command::calendar(tasks, filters, args, date_keys, now, env)
+            }
+            Command::Agenda => {
+                if !mods.is_empty() {
+                    return Err(CmdDisallowsMod);
+                }
+                Command::agenda(tasks, filters, args, date_keys, now, env)
+            }
+            Command::ExportCsv => {
+                Command::export_tasks(tasks, filters, args, date_keys, now, Format::Csv, env)
+            }
+            Command::ExportTsv => {
+                Command::export_tasks(tasks, filters, args, date_keys, now, Format::Tsv, env)
             }
-            Command::AddTask => Command::add_tasks(tasks, undo, mods, print_color),
+            Command::ExportMarkdown => {
+                Command::export_checklist(tasks, filters, args, date_keys, Checklist::Markdown, env)
+            }
+            Command::ExportOrg => {
+                Command::export_checklist(tasks, filters, args, date_keys, Checklist::Org, env)
+            }
+            Command::AddTask => Command::add_tasks(tasks, undo, mods, env),
             Command::RemoveTasks => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::remove_tasks(tasks, undo, filters, print_color)
+                Command::remove_tasks(tasks, undo, filters, env)
             }
             Command::ModifyTasks => {
-                Command::modify_tasks(tasks, undo, filters, mods, date_keys, print_color)
+                Command::modify_tasks(tasks, undo, filters, mods, date_keys, env)
             }
             Command::Undo => {
                 if !mods.is_empty() {
                     return Err(CmdDisallowsMod);
                 }
-                Command::undo(tasks, undo, print_color)
+                Command::undo(tasks, undo, env)
             }
         }
     }
 
-    fn list_tasks(tasks: String, filters: &[Filter], print_color: bool) -> Result<Output> {
-        let tasks = tasks
+    #[allow(clippy::too_many_arguments)]
+    fn list_tasks(
+        tasks: String,
+        filters: &[Filter],
+        args: &[&str],
+        date_keys: &[Key],
+        now: &Date,
+        width: Option<usize>,
+        env: &Env,
+    ) -> Result<Output> {
+        let paint = env.paint();
+        let ListOptions {
+            annotations: style,
+            sort,
+            group,
+            limit,
+            columns,
+            overflow,
+            dates,
+        } = ListOptions::new(args, date_keys)?;
+        let line_style = LineStyle {
+            annotations: &style,
+            dates,
+            now,
+            date_keys,
+        };
+
+        let mut tasks = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
             .collect::<Vec<_>>();
+        // Stable, so tasks which compare equal stay in file order.  Each keeps its line number.
+        tasks.sort_by(|(_, a), (_, b)| SortKey::compare(&sort, a, b, env));
+        if let Some(limit) = limit {
+            tasks.truncate(limit);
+        }
 
         let mut stdout = String::new();
-        let max_nr_digits = match tasks.last() {
-            Some((nr, _)) => nr.digits(),
+        let max_nr_digits = match tasks.iter().map(|(nr, _)| nr.digits()).max() {
+            Some(digits) => digits,
             None => return Ok(Output::JustPrint { stdout }),
         };
 
-        for (nr, task) in tasks {
+        let indent = match group {
+            Some(_) => ListOptions::GROUP_INDENT,
+            None => 0,
+        };
+        let table = match columns.is_empty() {
+            true => None,
+            false => {
+                let width = width.map(|width| width.saturating_sub(indent));
+                Some(Table::new(&columns, &tasks, now, width, overflow))
+            }
+        };
+        let print_header = |stdout: &mut String| {
+            if let Some(table) = &table {
+                table.print_header(stdout, indent, paint);
+            }
+        };
+        let print_task = |stdout: &mut String, i: usize| match &table {
+            Some(table) => {
+                let (nr, task) = &tasks[i];
+                table.print_row(stdout, i, indent, env.paint_task(task, *nr))
+            }
+            None => Command::print_list_task(
+                stdout,
+                (tasks[i].0, &tasks[i].1),
+                max_nr_digits,
+                indent,
+                &line_style,
+                env,
+            ),
+        };
+
+        let group = match group {
+            Some(group) => group,
+            None => {
+                print_header(&mut stdout);
+                for i in 0..tasks.len() {
+                    print_task(&mut stdout, i);
+                }
+                Fg::Default.print(&mut stdout, paint);
+                return Ok(Output::JustPrint { stdout });
+            }
+        };
+
+        // Sections in heading order, with the catch-all section last.
+        let mut sections: Vec<(String, Vec<usize>)> = Vec::new();
+        let mut ungrouped = Vec::new();
+        for (i, (_, task)) in tasks.iter().enumerate() {
+            let headings = group.headings(task);
+            if headings.is_empty() {
+                ungrouped.push(i);
+            }
+            for heading in headings {
+                match sections.iter_mut().find(|(h, _)| *h == heading) {
+                    Some((_, members)) => members.push(i),
+                    None => sections.push((heading, vec![i])),
+                }
+            }
+        }
+        sections.sort_by(|(a, _), (b, _)| group.compare(a, b));
+        if !ungrouped.is_empty() {
+            sections.push((Group::NONE.to_owned(), ungrouped));
+        }
+
+        for (heading, members) in sections {
+            match (&group, Project::new(&heading), Context::new(&heading)) {
+                (Group::Project, Some(proj), _) => proj.print(&mut stdout, paint),
+                (Group::Context, _, Some(ctx)) => ctx.print(&mut stdout, paint),
+                _ => {
+                    Fg::Default.print(&mut stdout, paint);
+                    stdout.push_str(&heading);
+                }
+            }
+            Fg::Default.print(&mut stdout, paint);
+            stdout.push(' ');
+            stdout.push_str(&members.len().to_string());
+            stdout.push('\n');
+
+            print_header(&mut stdout);
+            for i in members {
+                print_task(&mut stdout, i);
+            }
+        }
+
+        Fg::Default.print(&mut stdout, paint);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Prints one line of `list`, followed by any annotations shown on lines of their own.
+    fn print_list_task(
+        stdout: &mut String,
+        (nr, task): (Number, &Task),
+        max_nr_digits: usize,
+        indent: usize,
+        style: &LineStyle,
+        env: &Env,
+    ) {
+        for _ in 0..(indent + max_nr_digits - nr.digits()) {
+            stdout.push(' ');
+        }
+        nr.print(stdout, env.paint());
+        stdout.push(' ');
+
+        let pending = !task.is_completed();
+        let paint = env.paint_task(task, nr);
+        match style.annotations {
+            AnnotationStyle::Inline => {
+                for (token, _) in task.iter() {
+                    style.dates.print_token(
+                        stdout,
+                        &token,
+                        pending,
+                        style.now,
+                        style.date_keys,
+                        paint,
+                    );
+                }
+            }
+            AnnotationStyle::Indent | AnnotationStyle::Hide => {
+                let mut sections = Command::split_annotations(task).into_iter();
+                if let Some(body) = sections.next() {
+                    Command::print_tokens(stdout, body, pending, style, paint);
+                }
+                let indented = matches!(style.annotations, AnnotationStyle::Indent);
+                for note in sections.filter(|_| indented) {
+                    Fg::Default.print(stdout, paint);
+                    stdout.push('\n');
+                    for _ in 0..(indent + max_nr_digits + 1 + AnnotationStyle::INDENT_WIDTH) {
+                        stdout.push(' ');
+                    }
+                    Command::print_tokens(stdout, note, pending, style, paint);
+                }
+            }
+        }
+        stdout.push('\n');
+    }
+
+    // Lists pending tasks from most to least urgent, each with its urgency score.
+    fn list_next(tasks: String, filters: &[Filter], env: &Env) -> Result<Output> {
+        let paint = env.paint();
+        let mut tasks = tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+            .filter(|(_, t)| !t.is_completed())
+            .map(|(nr, task)| {
+                let score = env.urgency(&task);
+                (nr, task, score)
+            })
+            .collect::<Vec<_>>();
+        // Stable, so equally urgent tasks stay in file order.
+        tasks.sort_by(|(.., a), (.., b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        let tasks = tasks
+            .into_iter()
+            .map(|(nr, task, score)| (nr, task, format!("{:.1}", score)))
+            .collect::<Vec<_>>();
+
+        let mut stdout = String::new();
+        let max_nr_digits = tasks.iter().map(|(nr, ..)| nr.digits()).max().unwrap_or(0);
+        let max_score_len = tasks.iter().map(|(.., s)| s.len()).max().unwrap_or(0);
+
+        for (nr, task, score) in tasks {
             for _ in 0..(max_nr_digits - nr.digits()) {
                 stdout.push(' ');
             }
-            nr.print(&mut stdout, print_color);
+            nr.print(&mut stdout, paint);
+            stdout.push(' ');
+
+            Fg::Default.print(&mut stdout, paint);
+            for _ in 0..(max_score_len - score.len()) {
+                stdout.push(' ');
+            }
+            stdout.push_str(&score);
             stdout.push(' ');
 
-            task.print(&mut stdout, print_color);
+            task.print(&mut stdout, env.paint_task(&task, nr));
             stdout.push('\n');
         }
 
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
         Ok(Output::JustPrint { stdout })
     }
 
-    fn list_tokens(
+    // Prints month grids marking the days tasks fall on, optionally listing them by day.
+    fn calendar(
         tasks: String,
         filters: &[Filter],
-        tt: TokenType,
-        print_color: bool,
+        args: &[&str],
+        date_keys: &[Key],
+        now: &Date,
+        env: &Env,
+    ) -> Result<Output> {
+        let options = CalendarOptions::new(args, date_keys, now, env.calendar)?;
+        let tasks = tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+            .collect::<Vec<_>>();
+
+        let mut stdout = String::new();
+        Month::print_all(&mut stdout, &options, &tasks, now, env);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Lists the tasks falling on each of the coming days, with those overdue or undated.
+    fn agenda(
+        tasks: String,
+        filters: &[Filter],
+        args: &[&str],
+        date_keys: &[Key],
+        now: &Date,
+        env: &Env,
     ) -> Result<Output> {
+        let options = AgendaOptions::new(args, date_keys)?;
+        let tasks = tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+            .collect::<Vec<_>>();
+
+        let mut stdout = String::new();
+        Agenda::print(&mut stdout, &options, &tasks, date_keys, now, env);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Splits a task into its body followed by each of its annotations, dropping the `|` tokens.
+    fn split_annotations<'t>(task: &'t Task) -> Vec<Vec<Token<'t>>> {
+        let mut sections = vec![Vec::new()];
+        for (token, _) in task.iter() {
+            match (token, sections.last_mut()) {
+                (Token::Annotation(_), _) => sections.push(Vec::new()),
+                (token, Some(section)) => section.push(token),
+                (_, None) => {}
+            }
+        }
+        sections
+    }
+
+    // Prints the tokens with surrounding whitespace trimmed.
+    fn print_tokens(
+        stdout: &mut String,
+        tokens: Vec<Token>,
+        pending: bool,
+        style: &LineStyle,
+        paint: Paint,
+    ) {
+        let start = tokens.iter().position(|t| !matches!(t, Token::Space(_)));
+        let end = tokens.iter().rposition(|t| !matches!(t, Token::Space(_)));
+        if let (Some(start), Some(end)) = (start, end) {
+            for token in &tokens[start..=end] {
+                style
+                    .dates
+                    .print_token(stdout, token, pending, style.now, style.date_keys, paint);
+            }
+        }
+    }
+
+    fn list_tokens(tasks: String, filters: &[Filter], tt: TokenType, env: &Env) -> Result<Output> {
+        let paint = env.paint();
         let mut tokens = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
             .flat_map(|(_, task)| task.into_iter())
             .filter_map(|(token, _)| match (&tt, token) {
                 (TokenType::Projects, Token::Project(t)) => Some(Token::Project(t)),
@@ -158,12 +659,189 @@ impl<'a> Command {
         tokens.sort_unstable();
 
         for token in tokens {
-            token.print(&mut stdout, print_color);
+            token.print(&mut stdout, paint);
+            stdout.push('\n');
+        }
+        Fg::Default.print(&mut stdout, paint);
+
+        Fg::Default.print(&mut stdout, paint);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Lists projects or contexts nested by their `.`-separated levels, along with how many tasks
+    // fall under each.  Levels with no task of their own are still listed to complete the tree.
+    fn list_token_tree(
+        tasks: String,
+        filters: &[Filter],
+        tt: TokenType,
+        env: &Env,
+    ) -> Result<Output> {
+        let paint = env.paint();
+        let mut counts: BTreeMap<Vec<String>, usize> = BTreeMap::new();
+
+        for (_, task) in tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+        {
+            let mut nodes = HashSet::new();
+            for (token, _) in task.iter() {
+                let name = match (&tt, token) {
+                    (TokenType::Projects, Token::Project(t)) => t.as_str().to_owned(),
+                    (TokenType::Contexts, Token::Context(t)) => t.as_str().to_owned(),
+                    _ => continue,
+                };
+                let levels = name.split('.').map(|s| s.to_owned()).collect::<Vec<_>>();
+                for depth in 1..=levels.len() {
+                    nodes.insert(levels[..depth].to_vec());
+                }
+            }
+            for node in nodes {
+                *counts.entry(node).or_insert(0) += 1;
+            }
+        }
+
+        let mut stdout = String::new();
+        for (levels, count) in counts {
+            for _ in 1..levels.len() {
+                stdout.push_str("  ");
+            }
+            let name = levels.join(".");
+            match tt {
+                TokenType::Projects => Project::new(&name).unwrap().print(&mut stdout, paint),
+                _ => Context::new(&name).unwrap().print(&mut stdout, paint),
+            }
+            Fg::Default.print(&mut stdout, paint);
+            stdout.push(' ');
+            stdout.push_str(&count.to_string());
             stdout.push('\n');
         }
-        Fg::Default.print(&mut stdout, print_color);
 
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Lists the virtual tags which apply to at least one task, along with how many tasks they do.
+    fn list_virtual_tags(tasks: String, filters: &[Filter], env: &Env) -> Result<Output> {
+        let paint = env.paint();
+        let mut counts = [0; VirtualTag::ALL.len()];
+        for (_, task) in tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+        {
+            for (tag, count) in VirtualTag::ALL.iter().zip(counts.iter_mut()) {
+                if tag.is_set(&task, &env.tag_dates, &env.dependencies) {
+                    *count += 1;
+                }
+            }
+        }
+
+        let mut stdout = String::new();
+        for (tag, count) in VirtualTag::ALL.iter().zip(counts.iter()) {
+            if *count == 0 {
+                continue;
+            }
+            Project::new(tag.as_str())
+                .unwrap()
+                .print(&mut stdout, paint);
+            Fg::Default.print(&mut stdout, paint);
+            stdout.push(' ');
+            stdout.push_str(&count.to_string());
+            stdout.push('\n');
+        }
+
+        Fg::Default.print(&mut stdout, paint);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    // Lists report names, each followed by the comment opening its file, if any.
+    fn list_reports(reports: &[File], env: &Env) -> Result<Output> {
+        let paint = env.paint();
+        let mut reports = reports.iter().collect::<Vec<_>>();
+        reports.sort_by(|a, b| a.name.cmp(&b.name));
+        let width = reports.iter().map(|r| r.name.len()).max().unwrap_or(0);
+
+        let mut stdout = String::new();
+        for File { name, content } in reports {
+            Fg::Default.print(&mut stdout, paint);
+            stdout.push_str(name);
+            let description = content
+                .lines()
+                .next()
+                .and_then(|line| line.trim_start().strip_prefix('#'))
+                .map(str::trim)
+                .filter(|description| !description.is_empty());
+            if let Some(description) = description {
+                for _ in name.len()..width + 2 {
+                    stdout.push(' ');
+                }
+                Fg::Cyan.print(&mut stdout, paint);
+                stdout.push_str(description);
+            }
+            stdout.push('\n');
+        }
+
+        Fg::Default.print(&mut stdout, paint);
+        Ok(Output::JustPrint { stdout })
+    }
+
+    fn export_tasks(
+        tasks: String,
+        filters: &[Filter],
+        args: &[&str],
+        date_keys: &[Key],
+        now: &Date,
+        format: Format,
+        env: &Env,
+    ) -> Result<Output> {
+        let columns = match args.is_empty() {
+            true => Column::defaults(),
+            false => args
+                .iter()
+                .map(|arg| Column::new(arg, date_keys))
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        let mut stdout = String::new();
+        format.push_row(&mut stdout, columns.iter().map(|column| column.name()));
+
+        for (nr, task) in tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+        {
+            format.push_row(
+                &mut stdout,
+                columns.iter().map(|column| column.render(&task, nr, now)),
+            );
+        }
+
+        Ok(Output::JustPrint { stdout })
+    }
+
+    fn export_checklist(
+        tasks: String,
+        filters: &[Filter],
+        args: &[&str],
+        date_keys: &[Key],
+        checklist: Checklist,
+        env: &Env,
+    ) -> Result<Output> {
+        let options = ChecklistOptions::new(args, date_keys, &checklist)?;
+        let tasks = tasks
+            .lines()
+            .enumerate()
+            .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
+            .map(|(_, task)| task)
+            .collect::<Vec<_>>();
+
+        let mut stdout = String::new();
+        checklist.print(&mut stdout, &tasks, &options);
         Ok(Output::JustPrint { stdout })
     }
 
@@ -171,8 +849,9 @@ impl<'a> Command {
         tasks: String,
         mut undo: String,
         mods: &[Modification],
-        print_color: bool,
+        env: &Env,
     ) -> Result<Output> {
+        let paint = env.paint();
         let mut retained = tasks
             .lines()
             .map(|line| line.to_owned())
@@ -185,16 +864,16 @@ impl<'a> Command {
         for m in mods {
             let _ = match m {
                 Modification::SetBody(body) => {
-                    Modification::Append(body.clone()).apply(&mut buf, &[])
+                    Modification::Append(body.clone()).apply(&mut buf, &[], env.calendar)
                 }
-                _ => m.apply(&mut buf, &[]),
+                _ => m.apply(&mut buf, &[], env.calendar),
             };
         }
         retained.push(buf.as_str().to_owned());
 
-        Fg::Green.print(&mut stdout, print_color);
+        Fg::Green.print(&mut stdout, paint);
         stdout.push_str("ADD ");
-        buf.as_task().print(&mut stdout, print_color);
+        buf.as_task().print(&mut stdout, paint);
         stdout.push('\n');
 
         undo.push_str("ADD ");
@@ -207,7 +886,7 @@ impl<'a> Command {
             tasks.push('\n');
         }
 
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
         Ok(Output::WriteFiles {
             stdout,
             confirm: false,
@@ -220,37 +899,58 @@ impl<'a> Command {
         tasks: String,
         mut undo: String,
         filters: &[Filter],
-        print_color: bool,
+        env: &Env,
     ) -> Result<Output> {
+        let paint = env.paint();
         let removed = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
             .map(|(_, task)| task)
             .collect::<Vec<_>>();
         let mut retained = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| !filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| !filters.iter().all(|f| f.keep(t, *nr, env)))
             .map(|(_, task)| task.into_str())
             .collect::<Vec<_>>();
 
         let mut stdout = String::new();
         undo.push_str("---\n");
 
+        // Deleting a task leaves those depending on it dangling, so warn and ask first.
+        let mut dependents = 0;
         for task in &removed {
-            Fg::Red.print(&mut stdout, print_color);
+            let id = match Dependencies::id(task) {
+                Some(id) => id,
+                None => continue,
+            };
+            for dependent in retained
+                .iter()
+                .map(|line| Task::new(line))
+                .filter(|t| Dependencies::depends_on(t, id))
+            {
+                Fg::Yellow.print(&mut stdout, paint);
+                stdout.push_str("WARN ");
+                dependent.print(&mut stdout, paint);
+                stdout.push('\n');
+                dependents += 1;
+            }
+        }
+
+        for task in &removed {
+            Fg::Red.print(&mut stdout, paint);
             stdout.push_str("DEL ");
-            task.print(&mut stdout, print_color);
+            task.print(&mut stdout, paint);
             stdout.push('\n');
 
             undo.push_str("DEL ");
             undo.push_str(task.as_str());
             undo.push('\n');
         }
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
 
         retained.sort_unstable();
         let mut tasks = retained.join("\n");
@@ -258,10 +958,10 @@ impl<'a> Command {
             tasks.push('\n');
         }
 
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
         Ok(Output::WriteFiles {
             stdout,
-            confirm: removed.len() > 1,
+            confirm: removed.len() > 1 || dependents > 0,
             tasks,
             undo,
         })
@@ -273,20 +973,21 @@ impl<'a> Command {
         filters: &[Filter],
         mods: &[Modification],
         date_keys: &'a [Key<'a>],
-        print_color: bool,
+        env: &Env,
     ) -> Result<Output> {
+        let paint = env.paint();
         let modified = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| filters.iter().all(|f| f.keep(t, *nr, env)))
             .map(|(_, task)| task)
             .collect::<Vec<_>>();
         let mut retained = tasks
             .lines()
             .enumerate()
             .map(|(nr, line)| (Number::from_enumerate(nr), Task::new(line)))
-            .filter(|(nr, t)| !filters.iter().all(|f| f.keep(t, *nr)))
+            .filter(|(nr, t)| !filters.iter().all(|f| f.keep(t, *nr, env)))
             .map(|(_, task)| task.into_str().to_owned())
             .collect::<Vec<_>>();
         let mut added: Vec<String> = Vec::new();
@@ -307,7 +1008,7 @@ impl<'a> Command {
                 let ModOutput {
                     add: a,
                     remove_similar: r,
-                } = m.apply(&mut buf, date_keys);
+                } = m.apply(&mut buf, date_keys, env.calendar);
                 add = add.or(a);
                 remove_similar = remove_similar || r;
             }
@@ -319,9 +1020,9 @@ impl<'a> Command {
             added.push(buf.clone().into_string());
             changed += 1;
 
-            Fg::Red.print(&mut stdout, print_color);
+            Fg::Red.print(&mut stdout, paint);
             stdout.push_str("DEL ");
-            task.print(&mut stdout, print_color);
+            task.print(&mut stdout, paint);
             stdout.push('\n');
 
             undo.push_str("DEL ");
@@ -331,9 +1032,9 @@ impl<'a> Command {
             if let Some(buf) = add {
                 added.push(buf.as_str().to_owned());
 
-                Fg::Green.print(&mut stdout, print_color);
+                Fg::Green.print(&mut stdout, paint);
                 stdout.push_str("ADD ");
-                buf.as_task().print(&mut stdout, print_color);
+                buf.as_task().print(&mut stdout, paint);
                 stdout.push('\n');
 
                 undo.push_str("ADD ");
@@ -347,7 +1048,7 @@ impl<'a> Command {
                     let mut buf_len = 0;
                     let mut task_len = 0;
                     let mut mismatch = false;
-                    for ((a, ar), (b, br)) in buf.as_task().iter().zip(Task::new(&task).iter()) {
+                    for ((a, ar), (b, br)) in buf.as_task().iter().zip(Task::new(task).iter()) {
                         buf_len = ar.end;
                         task_len = br.end;
                         if a == b {
@@ -373,9 +1074,9 @@ impl<'a> Command {
                     }
                 }
                 if let Some(i) = remove {
-                    Fg::Red.print(&mut stdout, print_color);
+                    Fg::Red.print(&mut stdout, paint);
                     stdout.push_str("DEL ");
-                    Task::new(&retained[i]).print(&mut stdout, print_color);
+                    Task::new(&retained[i]).print(&mut stdout, paint);
                     stdout.push('\n');
 
                     undo.push_str("DEL ");
@@ -386,9 +1087,9 @@ impl<'a> Command {
                 }
             }
 
-            Fg::Green.print(&mut stdout, print_color);
+            Fg::Green.print(&mut stdout, paint);
             stdout.push_str("ADD ");
-            buf.as_task().print(&mut stdout, print_color);
+            buf.as_task().print(&mut stdout, paint);
             stdout.push('\n');
 
             undo.push_str("ADD ");
@@ -403,7 +1104,18 @@ impl<'a> Command {
             tasks.push('\n');
         }
 
-        Fg::Default.print(&mut stdout, print_color);
+        // Completing or removing a dependency may leave tasks waiting on it free to start.
+        let (before, after) = (&env.dependencies, Dependencies::new(&tasks));
+        for task in retained.iter().map(|line| Task::new(line)) {
+            if before.is_blocked(&task) && !after.is_blocked(&task) {
+                Fg::Cyan.print(&mut stdout, paint);
+                stdout.push_str("READY ");
+                task.print(&mut stdout, paint);
+                stdout.push('\n');
+            }
+        }
+
+        Fg::Default.print(&mut stdout, paint);
         Ok(Output::WriteFiles {
             stdout,
             confirm: changed > 1,
@@ -412,7 +1124,8 @@ impl<'a> Command {
         })
     }
 
-    fn undo(tasks: String, undo: String, print_color: bool) -> Result<Output> {
+    fn undo(tasks: String, undo: String, env: &Env) -> Result<Output> {
+        let paint = env.paint();
         let mut tasks = tasks.lines().collect::<Vec<_>>();
         let mut stdout = String::new();
         let mut new_undo_len = undo.lines().count();
@@ -430,9 +1143,9 @@ impl<'a> Command {
             match (head, tail, pos) {
                 (Some("---"), _, _) => break,
                 (Some("ADD"), Some(line), Some(pos)) => {
-                    Fg::Red.print(&mut stdout, print_color);
+                    Fg::Red.print(&mut stdout, paint);
                     stdout.push_str("DEL ");
-                    Task::new(line).print(&mut stdout, print_color);
+                    Task::new(line).print(&mut stdout, paint);
                     stdout.push('\n');
 
                     tasks.remove(pos);
@@ -441,9 +1154,9 @@ impl<'a> Command {
                     return Err(UndoMismatch(line.to_string()));
                 }
                 (Some("DEL"), Some(line), _) => {
-                    Fg::Green.print(&mut stdout, print_color);
+                    Fg::Green.print(&mut stdout, paint);
                     stdout.push_str("ADD ");
-                    Task::new(line).print(&mut stdout, print_color);
+                    Task::new(line).print(&mut stdout, paint);
                     stdout.push('\n');
 
                     tasks.push(line);
@@ -466,7 +1179,7 @@ impl<'a> Command {
             undo.push('\n');
         }
 
-        Fg::Default.print(&mut stdout, print_color);
+        Fg::Default.print(&mut stdout, paint);
         Ok(Output::WriteFiles {
             stdout,
             confirm: true,
@@ -2,13 +2,18 @@
 // All datetimes in chore are implicitly durations over the provided resolution.
 // For example, "2001-02-03" describes 2001-02-03T00:00:00 to 2001-02-03T23:59:59.
 //
+// Datetimes may optionally carry a UTC offset.  Those which do not are naive and, when compared
+// against one which does, are assumed to share its offset.
+//
 
+use crate::calendar::Calendar;
 use chrono::{Datelike, Timelike};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Date {
     start: chrono::NaiveDateTime,
     duration: self::Duration,
+    offset: Option<chrono::FixedOffset>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -22,16 +27,50 @@ pub enum Duration {
 }
 use Duration::*;
 
+impl Duration {
+    // Orders durations by their unit, ignoring the amount.
+    fn unit_rank(&self) -> u8 {
+        match self {
+            Seconds(_) => 0,
+            Minutes(_) => 1,
+            Hours(_) => 2,
+            Days(_) => 3,
+            Months(_) => 4,
+            Years(_) => 5,
+        }
+    }
+
+    fn unit(&self) -> Duration {
+        match self {
+            Seconds(_) => Seconds(1),
+            Minutes(_) => Minutes(1),
+            Hours(_) => Hours(1),
+            Days(_) => Days(1),
+            Months(_) => Months(1),
+            Years(_) => Years(1),
+        }
+    }
+}
+
 impl Date {
     pub fn from_chrono(date: &chrono::NaiveDateTime) -> Date {
         Date {
             start: date.with_nanosecond(0).unwrap(),
             duration: Seconds(1),
+            offset: None,
         }
     }
 
-    pub fn new(str: &str, cf: &Date) -> Option<Date> {
-        Date::from_abs(str).or_else(|| Date::from_rel(str, cf))
+    pub fn new(str: &str, cf: &Date, calendar: &Calendar) -> Option<Date> {
+        let date = Date::from_abs(str)
+            .or_else(|| Date::from_rel(str, cf, calendar))
+            .or_else(|| Date::from_expression(str, cf, calendar))?;
+        // Express user-provided dates in the same zone as the date they are relative to, if known.
+        match (date.offset, cf.offset) {
+            (Some(_), Some(offset)) => Some(date.to_offset(offset)),
+            (None, Some(offset)) => Some(date.with_offset(offset)),
+            (_, None) => Some(date),
+        }
     }
 
     pub fn from_abs(str: &str) -> Option<Date> {
@@ -39,6 +78,12 @@ impl Date {
         if str.is_empty() {
             return Date::from_fields(year, 1, 1, 0, 0, 0, Years(1));
         }
+        if let Some(str) = str.strip_prefix("-W") {
+            return Date::from_iso_week(year, str);
+        }
+        if let Some(str) = str.strip_prefix("-Q") {
+            return Date::from_quarter(year, str);
+        }
 
         let (month, str) = Date::parse_field(str, '-')?;
         if str.is_empty() {
@@ -54,29 +99,156 @@ impl Date {
         if str.is_empty() {
             return Date::from_fields(year, month, day, hour, 0, 0, Hours(1));
         }
+        // `+HH:MM` would be ambiguous with a malformed minute field; disallow it here.
+        if let Some(offset) = Some(str)
+            .filter(|str| !str.contains(':'))
+            .and_then(Date::parse_offset)
+        {
+            return Some(
+                Date::from_fields(year, month, day, hour, 0, 0, Hours(1))?.with_offset(offset),
+            );
+        }
 
         let (minute, str) = Date::parse_field(str, ':')?;
         if str.is_empty() {
             return Date::from_fields(year, month, day, hour, minute, 0, Minutes(1));
         }
+        if let Some(offset) = Date::parse_offset(str) {
+            return Some(
+                Date::from_fields(year, month, day, hour, minute, 0, Minutes(1))?
+                    .with_offset(offset),
+            );
+        }
 
         let (second, str) = Date::parse_field(str, ':')?;
         if str.is_empty() {
             return Date::from_fields(year, month, day, hour, minute, second, Seconds(1));
         }
+        if let Some(offset) = Date::parse_offset(str) {
+            return Some(
+                Date::from_fields(year, month, day, hour, minute, second, Seconds(1))?
+                    .with_offset(offset),
+            );
+        }
 
         None
     }
 
-    pub fn from_rel(str: &str, cf: &Date) -> Option<Date> {
+    // `WW` spans the whole ISO week; `WW-D` is a single day of it, counting from Monday as 1.
+    fn from_iso_week(year: i32, str: &str) -> Option<Date> {
+        let (week, day) = match (Date::parse_digits(str.get(0..2)?)?, str.get(2..)?) {
+            (week, "") => (week, None),
+            (week, day) => (week, Some(day.strip_prefix('-')?)),
+        };
+        let (weekday, duration) = match day {
+            None => (chrono::Weekday::Mon, Days(7)),
+            Some(day) => match Date::parse_digits::<u8>(day)? {
+                1 => (chrono::Weekday::Mon, Days(1)),
+                2 => (chrono::Weekday::Tue, Days(1)),
+                3 => (chrono::Weekday::Wed, Days(1)),
+                4 => (chrono::Weekday::Thu, Days(1)),
+                5 => (chrono::Weekday::Fri, Days(1)),
+                6 => (chrono::Weekday::Sat, Days(1)),
+                7 => (chrono::Weekday::Sun, Days(1)),
+                _ => return None,
+            },
+        };
+        Some(Date {
+            start: chrono::NaiveDate::from_isoywd_opt(year, week, weekday)?.and_hms_opt(0, 0, 0)?,
+            duration,
+            offset: None,
+        })
+    }
+
+    fn from_quarter(year: i32, str: &str) -> Option<Date> {
+        match Date::parse_digits::<u32>(str)? {
+            quarter @ 1..=4 if str.len() == 1 => {
+                Date::from_fields(year, (quarter - 1) * 3 + 1, 1, 0, 0, 0, Months(3))
+            }
+            _ => None,
+        }
+    }
+
+    // Unlike `str::parse`, rejects a leading sign.
+    fn parse_digits<T: std::str::FromStr>(str: &str) -> Option<T> {
+        match !str.is_empty() && str.bytes().all(|b| b.is_ascii_digit()) {
+            true => str.parse().ok(),
+            false => None,
+        }
+    }
+
+    // Parse a UTC offset in the forms `Z`, `UTC`, `+HHMM` or `+HH:MM`.
+    pub fn parse_offset(str: &str) -> Option<chrono::FixedOffset> {
+        if str == "Z" || str == "UTC" {
+            return chrono::FixedOffset::east_opt(0);
+        }
+        let (sign, str) = match (str.strip_prefix('+'), str.strip_prefix('-')) {
+            (Some(str), _) => (1, str),
+            (_, Some(str)) => (-1, str),
+            _ => return None,
+        };
+        let (hours, minutes) = match (str.len(), str.get(2..3)) {
+            (4, _) => (str.get(0..2)?, str.get(2..4)?),
+            (5, Some(":")) => (str.get(0..2)?, str.get(3..5)?),
+            _ => return None,
+        };
+        if !hours
+            .chars()
+            .chain(minutes.chars())
+            .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+        let hours = hours.parse::<i32>().ok().filter(|&h| h < 24)?;
+        let minutes = minutes.parse::<i32>().ok().filter(|&m| m < 60)?;
+        chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+
+    // Attach an offset to the date without changing its wall clock time.
+    pub fn with_offset(self, offset: chrono::FixedOffset) -> Date {
+        Date {
+            offset: Some(offset),
+            ..self
+        }
+    }
+
+    // Convert the date to the same instant in another offset.  Naive dates are only annotated.
+    pub fn to_offset(&self, offset: chrono::FixedOffset) -> Date {
+        let start = match self.offset {
+            Some(current) => {
+                self.start
+                    + chrono::Duration::seconds(
+                        (offset.local_minus_utc() - current.local_minus_utc()).into(),
+                    )
+            }
+            None => self.start,
+        };
+        Date {
+            start,
+            duration: self.duration.clone(),
+            offset: Some(offset),
+        }
+    }
+
+    // Drop the offset, keeping the wall clock time.
+    pub fn without_offset(&self) -> Date {
+        Date {
+            offset: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn from_rel(str: &str, cf: &Date, calendar: &Calendar) -> Option<Date> {
         Date::from_relative_hms(str, cf)
             .or_else(|| Date::from_relative_hm(str, cf))
             .or_else(|| Date::from_relative_hour(str, cf))
             .or_else(|| Date::from_relative_weekday(str, cf))
             .or_else(|| Date::from_relative_day_of_month(str, cf))
             .or_else(|| Date::from_relative_month(str, cf))
-            .or_else(|| Date::from_relative_offset(str, cf))
+            .or_else(|| Date::from_relative_offset(str, cf, calendar))
             .or_else(|| Date::from_relative_named(str, cf))
+            .or_else(|| Date::from_relative_period(str, cf))
+            .or_else(|| Date::from_relative_phrase(str, cf, calendar))
     }
 
     fn from_relative_hms(str: &str, cf: &Date) -> Option<Date> {
@@ -84,6 +256,7 @@ impl Date {
         let date = Date {
             start: cf.start,
             duration: Seconds(1),
+            offset: cf.offset,
         };
         Some(Date::next_time(date, &target))
     }
@@ -93,6 +266,7 @@ impl Date {
         let date = Date {
             start: cf.start,
             duration: Minutes(1),
+            offset: cf.offset,
         };
         Some(Date::next_time(date, &target))
     }
@@ -103,6 +277,7 @@ impl Date {
         let date = Date {
             start: cf.start,
             duration: Hours(1),
+            offset: cf.offset,
         };
         Some(Date::next_time(date, &target))
     }
@@ -115,6 +290,7 @@ impl Date {
         let date = Date {
             start: cf.start.with_second(0)?.with_minute(0)?.with_hour(0)?,
             duration: Days(1),
+            offset: cf.offset,
         };
 
         if target_weekday > current_weekday {
@@ -138,6 +314,7 @@ impl Date {
         let mut date = Date {
             start: cf.start.with_second(0)?.with_minute(0)?.with_hour(0)?,
             duration: Days(1),
+            offset: cf.offset,
         };
         let day_delta = Days(target_day - current_day);
 
@@ -161,6 +338,7 @@ impl Date {
                 .with_hour(0)?
                 .with_day(1)?,
             duration: Months(1),
+            offset: cf.offset,
         };
 
         if target_month > current_month {
@@ -170,7 +348,13 @@ impl Date {
         }
     }
 
-    fn from_relative_offset(str: &str, cf: &Date) -> Option<Date> {
+    fn from_relative_offset(str: &str, cf: &Date, calendar: &Calendar) -> Option<Date> {
+        Some(cf + Date::relative_duration(str, cf, calendar)?)
+    }
+
+    // The amount of time described by an offset such as `3d`, which for week days depends on the
+    // day it is counted from.
+    fn relative_duration(str: &str, cf: &Date, calendar: &Calendar) -> Option<Duration> {
         let offset = str
             .strip_suffix('s')
             .and_then(|str| str.parse::<i64>().ok())
@@ -193,33 +377,8 @@ impl Date {
             .or_else(|| {
                 str.strip_suffix('W')
                     .and_then(|str| str.parse::<i64>().ok())
-                    // Handle first sub-week section which goes through a
-                    // weekend directly
-                    .map(|dur| match (dur, cf.start.weekday()) {
-                        (i64::MIN..=-1, chrono::Weekday::Sun) => (dur + 1, -2),
-                        (0, chrono::Weekday::Sun) => (dur, 0),
-                        (1..=i64::MAX, chrono::Weekday::Sun) => (dur - 1, 1),
-                        (i64::MIN..=-1, chrono::Weekday::Mon) => (dur + 1, -3),
-                        (0..=i64::MAX, chrono::Weekday::Mon) => (dur, 0),
-                        (i64::MIN..=-2, chrono::Weekday::Tue) => (dur + 2, -4),
-                        (-1..=3, chrono::Weekday::Tue) => (dur, 0),
-                        (4..=i64::MAX, chrono::Weekday::Tue) => (dur - 4, 6),
-                        (i64::MIN..=-3, chrono::Weekday::Wed) => (dur + 3, -5),
-                        (-2..=2, chrono::Weekday::Wed) => (dur, 0),
-                        (3..=i64::MAX, chrono::Weekday::Wed) => (dur - 3, 5),
-                        (i64::MIN..=-4, chrono::Weekday::Thu) => (dur + 4, -6),
-                        (-3..=1, chrono::Weekday::Thu) => (dur, 0),
-                        (2..=i64::MAX, chrono::Weekday::Thu) => (dur - 2, 4),
-                        (i64::MIN..=-5, chrono::Weekday::Fri) => (dur + 5, -7),
-                        (-4..=0, chrono::Weekday::Fri) => (dur, 0),
-                        (1..=i64::MAX, chrono::Weekday::Fri) => (dur - 1, 3),
-                        (i64::MIN..=-6, chrono::Weekday::Sat) => (dur + 6, -8),
-                        (-5, chrono::Weekday::Sat) => (dur + 5, -5),
-                        (-4..=0, chrono::Weekday::Sat) => (dur, 0),
-                        (1..=i64::MAX, chrono::Weekday::Sat) => (dur -1, 2),
-                    })
-                    // Do rest by converting every five weekdays to seven days
-                    .map(|(dur, adj)| Days((dur / 5 * 7) + (dur % 5) + adj))
+                    .and_then(|dur| calendar.business_days(&cf.start.date(), dur))
+                    .map(Days)
             })
             .or_else(|| {
                 str.strip_suffix('w')
@@ -237,7 +396,7 @@ impl Date {
                     .map(Years)
             })?;
 
-        Some(cf + offset)
+        Some(offset)
     }
 
     fn from_relative_named(str: &str, cf: &Date) -> Option<Date> {
@@ -257,10 +416,214 @@ impl Date {
             Date {
                 start: cf.start.with_second(0)?.with_minute(0)?.with_hour(0)?,
                 duration: Duration::Days(1),
+                offset: cf.offset,
             } + offset,
         )
     }
 
+    // Abbreviated boundaries of the week, month, quarter or year containing cf.
+    fn from_relative_period(str: &str, cf: &Date) -> Option<Date> {
+        match str {
+            "sow" => Date::start_of_period("w", cf),
+            "som" => Date::start_of_period("m", cf),
+            "soq" => Date::start_of_period("q", cf),
+            "soy" => Date::start_of_period("y", cf),
+            "eow" => Date::end_of_period("w", cf),
+            "eom" => Date::end_of_period("m", cf),
+            "eoq" => Date::end_of_period("q", cf),
+            "eoy" => Date::end_of_period("y", cf),
+            _ => Date::from_relative_whole_period(str, cf),
+        }
+    }
+
+    // The whole week, month, quarter or year containing cf, or the one before or after it, e.g.
+    // `thisweek` or `nextquarter`.
+    fn from_relative_whole_period(str: &str, cf: &Date) -> Option<Date> {
+        let (shift, period) = match str.get(0..4)? {
+            "last" => (-1, str.get(4..)?),
+            "this" => (0, str.get(4..)?),
+            "next" => (1, str.get(4..)?),
+            _ => return None,
+        };
+        let (unit, duration) = match period {
+            "week" => ("w", Days(7)),
+            "month" => ("m", Months(1)),
+            "quarter" => ("q", Months(3)),
+            "year" => ("y", Years(1)),
+            _ => return None,
+        };
+        let shift = match &duration {
+            Days(n) => Days(n * shift),
+            Months(n) => Months(n * shift),
+            _ => Years(shift as i32),
+        };
+        let start = Date::start_of_period(unit, cf)?;
+        Some(
+            Date {
+                start: start.start,
+                duration,
+                offset: start.offset,
+            } + shift,
+        )
+    }
+
+    // Multi-word descriptions, separated by whitespace, `-` or `_`, e.g. `next friday`,
+    // `last-monday`, `in 2 weeks` or `end_of_month`.
+    fn from_relative_phrase(str: &str, cf: &Date, calendar: &Calendar) -> Option<Date> {
+        let words = str
+            .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+
+        match words.as_slice() {
+            [which @ ("last" | "this" | "next"), period] if Date::is_period_name(period) => {
+                Date::from_relative_whole_period(&format!("{}{}", which, period), cf)
+            }
+            ["next", name] => {
+                Date::from_relative_weekday(name, cf).or_else(|| Date::from_relative_month(name, cf))
+            }
+            ["last", name] => {
+                Date::from_previous_weekday(name, cf).or_else(|| Date::from_previous_month(name, cf))
+            }
+            ["in", offset] => Date::from_relative_offset(offset, cf, calendar),
+            ["in", count, unit] => {
+                let count = count.parse::<i64>().ok()?;
+                let suffix = match unit.strip_suffix('s').unwrap_or(unit) {
+                    "second" | "sec" => "s",
+                    "minute" | "min" => "m",
+                    "hour" => "h",
+                    "day" => "d",
+                    "weekday" => "W",
+                    "week" => "w",
+                    "month" => "M",
+                    "year" => "y",
+                    _ => return None,
+                };
+                Date::from_relative_offset(&format!("{}{}", count, suffix), cf, calendar)
+            }
+            ["start", "of", period] => Date::start_of_period(period.get(0..1)?, cf)
+                .filter(|_| Date::is_period_name(period)),
+            ["end", "of", period] => Date::end_of_period(period.get(0..1)?, cf)
+                .filter(|_| Date::is_period_name(period)),
+            _ => None,
+        }
+    }
+
+    // Mirrors from_relative_weekday: the most recent matching day strictly before cf.
+    fn from_previous_weekday(str: &str, cf: &Date) -> Option<Date> {
+        let next = Date::from_relative_weekday(str, cf)?;
+        match (next.start.date() - cf.start.date()).num_days() {
+            7 => Some(next + Days(-14)),
+            _ => Some(next + Days(-7)),
+        }
+    }
+
+    // Mirrors from_relative_month: the most recent matching month strictly before cf.
+    fn from_previous_month(str: &str, cf: &Date) -> Option<Date> {
+        let next = Date::from_relative_month(str, cf)?;
+        match next.start.month() == cf.start.month() {
+            true => Some(next + Years(-2)),
+            false => Some(next + Years(-1)),
+        }
+    }
+
+    fn is_period_name(str: &str) -> bool {
+        matches!(str, "week" | "month" | "quarter" | "year")
+    }
+
+    // First day of the period (`w`eek, `m`onth, `q`uarter or `y`ear) containing cf.  Weeks start
+    // on Monday.
+    fn start_of_period(period: &str, cf: &Date) -> Option<Date> {
+        let day = cf.start.with_second(0)?.with_minute(0)?.with_hour(0)?;
+        let start = match period {
+            "w" => day - chrono::Duration::days(day.weekday().num_days_from_monday().into()),
+            "m" => day.with_day(1)?,
+            "q" => day.with_day(1)?.with_month0(day.month0() / 3 * 3)?,
+            "y" => day.with_day(1)?.with_month(1)?,
+            _ => return None,
+        };
+        Some(Date {
+            start,
+            duration: Days(1),
+            offset: cf.offset,
+        })
+    }
+
+    // Last day of the period (`w`eek, `m`onth, `q`uarter or `y`ear) containing cf.
+    fn end_of_period(period: &str, cf: &Date) -> Option<Date> {
+        let length = match period {
+            "w" => Days(7),
+            "m" => Months(1),
+            "q" => Months(3),
+            "y" => Years(1),
+            _ => return None,
+        };
+        Some(Date::start_of_period(period, cf)? + length + Days(-1))
+    }
+
+    // A base date followed by one or more signed offsets, e.g. `fri+2d` or `tomorrow-3h`.
+    fn from_expression(str: &str, cf: &Date, calendar: &Calendar) -> Option<Date> {
+        let (base, offsets) = Date::split_expression(str)?;
+        Date::from_abs(base)
+            .or_else(|| Date::from_rel(base, cf, calendar))?
+            .add_offsets(offsets, calendar)
+    }
+
+    // Splits trailing signed offsets from their base, e.g. `due-3d+1h` into `due` and `-3d+1h`.
+    // Returns None if there are no such offsets or nothing precedes them.
+    pub fn split_expression(str: &str) -> Option<(&str, &str)> {
+        let mut end = str.len();
+        while let Some(i) = str[..end].rfind(['+', '-']) {
+            match Date::is_offset(&str[i + 1..end]) {
+                true => end = i,
+                false => break,
+            }
+        }
+        match end {
+            0 => None,
+            end if end == str.len() => None,
+            end => Some((&str[..end], &str[end..])),
+        }
+    }
+
+    fn is_offset(str: &str) -> bool {
+        match str.char_indices().last() {
+            Some((i, unit)) if i > 0 => {
+                "smhdWwMy".contains(unit) && str[..i].chars().all(|c| c.is_ascii_digit())
+            }
+            _ => false,
+        }
+    }
+
+    // Applies signed offsets such as `-3d+1h` in order.  An offset finer than the date's
+    // resolution narrows it, so `tomorrow-3h` describes an hour rather than a day.
+    pub fn add_offsets(self, offsets: &str, calendar: &Calendar) -> Option<Date> {
+        let mut date = self;
+        let mut rest = offsets;
+        while !rest.is_empty() {
+            let len = rest[1..]
+                .find(['+', '-'])
+                .map_or(rest.len(), |i| i + 1);
+            let (offset, tail) = rest.split_at(len);
+            if !Date::is_offset(&offset[1..]) {
+                return None;
+            }
+            let duration = Date::relative_duration(offset, &date, calendar)?;
+            date += duration.clone();
+            // Offsets which do not preserve a week or quarter also narrow it, e.g. `2021-W05+1d`.
+            let keeps_resolution = match (&duration, &date.duration) {
+                (Days(offset), Days(span)) => offset % span == 0,
+                (Months(offset), Months(span)) => offset % span == 0,
+                (offset, span) => offset.unit_rank() >= span.unit_rank(),
+            };
+            if !keeps_resolution {
+                date.duration = duration.unit();
+            }
+            rest = tail;
+        }
+        Some(date)
+    }
+
     fn parse_field(str: &str, sep: char) -> Option<(u32, &str)> {
         if !str.starts_with(sep) {
             None
@@ -286,6 +649,7 @@ impl Date {
             start: chrono::NaiveDate::from_ymd_opt(year, month, day)?
                 .and_hms_opt(hour, minute, second)?,
             duration,
+            offset: None,
         })
     }
 
@@ -308,26 +672,211 @@ impl Date {
     }
 
     pub fn within(&self, other: &Date) -> bool {
-        other.start <= self.start && other.end() >= self.end()
+        other.utc_start(self) <= self.utc_start(other) && other.utc_end(self) >= self.utc_end(other)
     }
 
     pub fn before(&self, other: &Date) -> bool {
-        self.end() <= other.start
+        self.utc_end(other) <= other.utc_start(self)
     }
 
     pub fn after(&self, other: &Date) -> bool {
-        other.end() <= self.start
+        other.utc_end(self) <= self.utc_start(other)
+    }
+
+    // Within the span from the start of `start` to the end of `end`; a missing bound is open.
+    pub fn between(&self, start: Option<&Date>, end: Option<&Date>) -> bool {
+        let after_start = match start {
+            Some(start) => start.utc_start(self) <= self.utc_start(start),
+            None => true,
+        };
+        let before_end = match end {
+            Some(end) => self.utc_end(end) <= end.utc_end(self),
+            None => true,
+        };
+        after_start && before_end
+    }
+
+    // The day a date with a time falls on; dates of a day or longer are left as they are.
+    pub fn to_day(&self) -> Date {
+        match self.duration {
+            Years(_) | Months(_) | Days(_) => self.clone(),
+            Hours(_) | Minutes(_) | Seconds(_) => Date {
+                start: self.start.date().and_hms_opt(0, 0, 0).unwrap(),
+                duration: Days(1),
+                offset: self.offset,
+            },
+        }
+    }
+
+    // The month this date starts in.
+    pub fn to_month(&self) -> Date {
+        Date {
+            start: self.start.date().with_day(1).unwrap().and_hms_opt(0, 0, 0).unwrap(),
+            duration: Months(1),
+            offset: self.offset,
+        }
+    }
+
+    // The first day of this date, e.g. to step through a month day by day.
+    pub fn first_day(&self) -> Date {
+        Date {
+            start: self.start.date().and_hms_opt(0, 0, 0).unwrap(),
+            duration: Days(1),
+            offset: self.offset,
+        }
+    }
+
+    // Whether any part of this date's span falls within the other's.
+    pub fn overlaps(&self, other: &Date) -> bool {
+        self.utc_start(other) < other.utc_end(self) && other.utc_start(self) < self.utc_end(other)
+    }
+
+    pub fn day_of_month(&self) -> u32 {
+        self.start.day()
+    }
+
+    // Days since the Monday of the week this date starts in.
+    pub fn day_of_week(&self) -> u32 {
+        self.start.weekday().num_days_from_monday()
+    }
+
+    // Orders dates by when they start, then by which ends first.
+    pub fn compare(&self, other: &Date) -> std::cmp::Ordering {
+        self.utc_start(other)
+            .cmp(&other.utc_start(self))
+            .then(self.utc_end(other).cmp(&other.utc_end(self)))
+    }
+
+    // Days from the start of the other date to the start of this one; negative if this is earlier.
+    pub fn days_since(&self, other: &Date) -> f64 {
+        (self.utc_start(other) - other.utc_start(self)).num_seconds() as f64 / 86400.0
+    }
+
+    // Days from the start of this date to the end of the other; negative once the other is over.
+    pub fn days_until_end(&self, other: &Date) -> f64 {
+        (other.utc_end(self) - self.utc_start(other)).num_seconds() as f64 / 86400.0
+    }
+
+    // Days from now until this date starts, or negative days since it ended, and zero while it is
+    // under way.  Dates of a day or longer count whole days from today, so one due today is zero
+    // days away and one due yesterday a day past.
+    pub fn days_from(&self, now: &Date) -> f64 {
+        let (now, last_day) = match self.duration {
+            Years(_) | Months(_) | Days(_) => (now.to_day(), 1.0),
+            Hours(_) | Minutes(_) | Seconds(_) => (now.clone(), 0.0),
+        };
+        match (self.after(&now), self.before(&now)) {
+            (true, _) => self.days_since(&now),
+            (_, true) => now.days_until_end(self) - last_day,
+            _ => 0.0,
+        }
+    }
+
+    // The span this date covers named as though it contains now, e.g. "today" or "this month".
+    pub fn current_name(&self) -> &'static str {
+        match self.duration {
+            Years(_) => "this year",
+            Months(3) => "this quarter",
+            Months(_) => "this month",
+            Days(7) => "this week",
+            Days(_) => "today",
+            Hours(_) | Minutes(_) | Seconds(_) => "now",
+        }
+    }
+
+    // A terse magnitude for a number of days in the largest unit which fits, e.g. "3d", "2w",
+    // "5mo" or "1y".  Spans under a day are shown in hours, minutes or seconds.
+    pub fn span(days: f64) -> String {
+        const DAY: i64 = 24 * 60 * 60;
+        let secs = (days.abs() * DAY as f64).round() as i64;
+        let (amount, unit) = match secs {
+            0 => (0, "d"),
+            s if s < 60 => (s, "s"),
+            s if s < 60 * 60 => (s / 60, "min"),
+            s if s < DAY => (s / (60 * 60), "h"),
+            s if s < 14 * DAY => (s / DAY, "d"),
+            s if s < 60 * DAY => (s / (7 * DAY), "w"),
+            s if s < 365 * DAY => (s / (30 * DAY), "mo"),
+            s => (s / (365 * DAY), "y"),
+        };
+        format!("{}{}", amount, unit)
     }
 
     fn end(&self) -> chrono::NaiveDateTime {
         (self + &self.duration).start
     }
+
+    // Start in UTC, borrowing the other date's offset if this one is naive.  If both are naive
+    // both are left as-is, which is equivalent for comparison purposes.
+    fn utc_start(&self, other: &Date) -> chrono::NaiveDateTime {
+        match self.offset.or(other.offset) {
+            Some(offset) => self.start - chrono::Duration::seconds(offset.local_minus_utc().into()),
+            None => self.start,
+        }
+    }
+
+    fn utc_end(&self, other: &Date) -> chrono::NaiveDateTime {
+        match self.offset.or(other.offset) {
+            Some(offset) => self.end() - chrono::Duration::seconds(offset.local_minus_utc().into()),
+            None => self.end(),
+        }
+    }
+
+    // Format with full second resolution irrelevant of the date's duration.
+    pub fn to_timestamp(&self) -> String {
+        let mut str = self.start.format("%Y-%m-%dT%H:%M:%S").to_string();
+        if let Some(offset) = self.offset {
+            str.push_str(&Date::fmt_offset(offset, ":"));
+        }
+        str
+    }
+
+    // Format as an Org-mode timestamp body, e.g. "2001-02-03 Sat 04:05", omitting the time for
+    // dates with a resolution of a day or longer.
+    pub fn to_org(&self) -> String {
+        match self.duration {
+            Years(_) | Months(_) | Days(_) => self.start.format("%Y-%m-%d %a").to_string(),
+            Hours(_) | Minutes(_) | Seconds(_) => {
+                self.start.format("%Y-%m-%d %a %H:%M").to_string()
+            }
+        }
+    }
+}
+
+impl Date {
+    fn fmt_offset(offset: chrono::FixedOffset, separator: &str) -> String {
+        let seconds = offset.local_minus_utc();
+        if seconds == 0 {
+            return "Z".to_owned();
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let minutes = seconds.abs() / 60;
+        format!("{}{:02}{}{:02}", sign, minutes / 60, separator, minutes % 60)
+    }
 }
 
 impl std::fmt::Display for Date {
+    // Offsets are only meaningful, and thus only printed, for dates with a time component.
     fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let offset = match (&self.duration, self.offset) {
+            (Hours(_), Some(offset)) => Date::fmt_offset(offset, ""),
+            (Minutes(_) | Seconds(_), Some(offset)) => Date::fmt_offset(offset, ":"),
+            _ => String::new(),
+        };
         match self.duration {
             Years(_) => write!(fmt, "{:04}", self.start.year()),
+            Months(3) if matches!(self.start.month0(), 0 | 3 | 6 | 9) => write!(
+                fmt,
+                "{:04}-Q{}",
+                self.start.year(),
+                self.start.month0() / 3 + 1
+            ),
+            Days(7) if self.start.weekday() == chrono::Weekday::Mon => write!(
+                fmt,
+                "{:04}-W{:02}",
+                self.start.iso_week().year(),
+                self.start.iso_week().week()
+            ),
             Months(_) => {
                 write!(fmt, "{:04}-{:02}", self.start.year(), self.start.month())
             }
@@ -340,30 +889,33 @@ impl std::fmt::Display for Date {
             ),
             Hours(_) => write!(
                 fmt,
-                "{:04}-{:02}-{:02}T{:02}",
+                "{:04}-{:02}-{:02}T{:02}{}",
                 self.start.year(),
                 self.start.month(),
                 self.start.day(),
-                self.start.hour()
+                self.start.hour(),
+                offset
             ),
             Minutes(_) => write!(
                 fmt,
-                "{:04}-{:02}-{:02}T{:02}:{:02}",
+                "{:04}-{:02}-{:02}T{:02}:{:02}{}",
                 self.start.year(),
                 self.start.month(),
                 self.start.day(),
                 self.start.hour(),
-                self.start.minute()
+                self.start.minute(),
+                offset
             ),
             Seconds(_) => write!(
                 fmt,
-                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
                 self.start.year(),
                 self.start.month(),
                 self.start.day(),
                 self.start.hour(),
                 self.start.minute(),
-                self.start.second()
+                self.start.second(),
+                offset
             ),
         }
     }
@@ -419,6 +971,7 @@ impl std::ops::Add<&Duration> for &Date {
         Date {
             start,
             duration: self.duration.clone(),
+            offset: self.offset,
         }
     }
 }
@@ -518,12 +1071,202 @@ mod tests {
             let abs_input = Date::from_abs(input);
             assert_eq!(abs_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
     }
 
+    #[test]
+    fn from_abs_week_and_quarter() {
+        let date = |ymd: &str, duration| {
+            Some(Date {
+                start: chrono::NaiveDate::parse_from_str(ymd, "%Y-%m-%d")
+                    .unwrap()
+                    .and_hms(0, 0, 0),
+                duration,
+                offset: None,
+            })
+        };
+        for (input, expect, display) in &[
+            ("2021-W05", date("2021-02-01", Days(7)), "2021-W05"),
+            ("2021-W05-3", date("2021-02-03", Days(1)), "2021-02-03"),
+            ("2021-W05-7", date("2021-02-07", Days(1)), "2021-02-07"),
+            ("2019-W01", date("2018-12-31", Days(7)), "2019-W01"),
+            ("2020-W53", date("2020-12-28", Days(7)), "2020-W53"),
+            ("2021-Q1", date("2021-01-01", Months(3)), "2021-Q1"),
+            ("2021-Q2", date("2021-04-01", Months(3)), "2021-Q2"),
+            ("2021-Q4", date("2021-10-01", Months(3)), "2021-Q4"),
+            // unhappy path
+            ("2021-W53", None, ""),
+            ("2021-W00", None, ""),
+            ("2021-W5", None, ""),
+            ("2021-W05-0", None, ""),
+            ("2021-W05-8", None, ""),
+            ("2021-W+5", None, ""),
+            ("2021-W05-+3", None, ""),
+            ("2021-Q+", None, ""),
+            ("2021-W05-", None, ""),
+            ("2021-W05T01", None, ""),
+            ("2021-Q0", None, ""),
+            ("2021-Q5", None, ""),
+            ("2021-Q12", None, ""),
+        ] {
+            let abs_input = Date::from_abs(input);
+            assert_eq!(abs_input, *expect);
+            if let Some(abs_input) = abs_input {
+                assert_eq!(&abs_input.to_string(), display);
+            }
+        }
+
+        // Offsets keep whole weeks and quarters only while they stay aligned.
+        for (input, expect) in &[
+            ("2021-W05+1w", "2021-W06"),
+            ("2021-W05+1d", "2021-02-02"),
+            ("2021-Q1+3M", "2021-Q2"),
+            ("2021-Q1+1M", "2021-02"),
+            ("2021-Q1+1y", "2022-Q1"),
+        ] {
+            let date = Date::new(input, &test_now(), &Calendar::default())
+                .map(|date| date.to_string());
+            assert_eq!(date.as_deref(), Some(*expect));
+        }
+    }
+
+    #[test]
+    fn from_relative_whole_period() {
+        // input is relative to Saturday 2001-02-03T04:05:06
+        for (input, expect) in &[
+            ("thisweek", Some("2001-W05")),
+            ("nextweek", Some("2001-W06")),
+            ("lastweek", Some("2001-W04")),
+            ("next week", Some("2001-W06")),
+            ("thismonth", Some("2001-02")),
+            ("lastmonth", Some("2001-01")),
+            ("thisquarter", Some("2001-Q1")),
+            ("nextquarter", Some("2001-Q2")),
+            ("last-quarter", Some("2000-Q4")),
+            ("thisyear", Some("2001")),
+            ("next_year", Some("2002")),
+            // unhappy path
+            ("thisday", None),
+            ("this", None),
+            ("nextweeks", None),
+            ("this friday", None),
+        ] {
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default())
+                .map(|date| date.to_string());
+            assert_eq!(rel_input.as_deref(), *expect);
+        }
+    }
+
+    #[test]
+    fn from_abs_offset() {
+        let utc = chrono::FixedOffset::east_opt(0).unwrap();
+        let cet = chrono::FixedOffset::east_opt(3600).unwrap();
+        let est = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let nst = chrono::FixedOffset::west_opt(3 * 3600 + 30 * 60).unwrap();
+        for (input, expect) in &[
+            (
+                "2001-02-03T04:05:06Z",
+                Date::from_fields(2001, 2, 3, 4, 5, 6, Seconds(1)).map(|d| d.with_offset(utc)),
+            ),
+            (
+                "2001-02-03T04:05UTC",
+                Date::from_fields(2001, 2, 3, 4, 5, 0, Minutes(1)).map(|d| d.with_offset(utc)),
+            ),
+            (
+                "2001-02-03T04Z",
+                Date::from_fields(2001, 2, 3, 4, 0, 0, Hours(1)).map(|d| d.with_offset(utc)),
+            ),
+            (
+                "2001-02-03T04:05+01:00",
+                Date::from_fields(2001, 2, 3, 4, 5, 0, Minutes(1)).map(|d| d.with_offset(cet)),
+            ),
+            (
+                "2001-02-03T04:05+0100",
+                Date::from_fields(2001, 2, 3, 4, 5, 0, Minutes(1)).map(|d| d.with_offset(cet)),
+            ),
+            (
+                "2001-02-03T04:05:06-05:00",
+                Date::from_fields(2001, 2, 3, 4, 5, 6, Seconds(1)).map(|d| d.with_offset(est)),
+            ),
+            (
+                "2001-02-03T04:05-0330",
+                Date::from_fields(2001, 2, 3, 4, 5, 0, Minutes(1)).map(|d| d.with_offset(nst)),
+            ),
+            // an hour may carry an offset without a colon
+            (
+                "2001-02-03T04-0500",
+                Date::from_fields(2001, 2, 3, 4, 0, 0, Hours(1)).map(|d| d.with_offset(est)),
+            ),
+            // unhappy path: offsets require a time
+            ("2001-02-03Z", None),
+            ("2001-02Z", None),
+            // unhappy path: ambiguous with minutes
+            ("2001-02-03T04+01:00", None),
+            // unhappy path: malformed offsets
+            ("2001-02-03T04:05+01", None),
+            ("2001-02-03T04:05+1", None),
+            ("2001-02-03T04:05+01:0", None),
+            ("2001-02-03T04:05+24:00", None),
+            ("2001-02-03T04:05+01:60", None),
+            ("2001-02-03T04:05+01-00", None),
+            ("2001-02-03T04:05+0x:00", None),
+            ("2001-02-03T04z", None),
+        ] {
+            assert_eq!(Date::from_abs(input), *expect);
+        }
+    }
+
+    #[test]
+    fn new_with_offset() {
+        let cet = chrono::FixedOffset::east_opt(3600).unwrap();
+        let now = test_now().with_offset(cet);
+        for (input, expect) in &[
+            // naive input is assumed to be in the same zone as `now`
+            ("2001-02-03T10", "2001-02-03T10+0100"),
+            ("2001-02-03T10:30", "2001-02-03T10:30+01:00"),
+            ("2001-02-03", "2001-02-03"),
+            // input with an offset is converted into `now`'s zone
+            ("2001-02-03T10Z", "2001-02-03T11+0100"),
+            ("2001-02-03T23:30-01:00", "2001-02-04T01:30+01:00"),
+            // relative input inherits `now`'s zone
+            ("now", "2001-02-03T04:05:06+01:00"),
+            ("2h", "2001-02-03T06:05:06+01:00"),
+            ("tomorrow", "2001-02-04"),
+        ] {
+            let date = Date::new(input, &now, &Calendar::default()).unwrap();
+            assert_eq!(date.to_string(), *expect);
+            assert_eq!(Date::new(&date.to_string(), &now, &Calendar::default()), Some(date));
+        }
+    }
+
+    #[test]
+    fn compare_offsets() {
+        for (left, right, before, after, within) in &[
+            // same instant in different zones
+            ("2001-02-03T04:00Z", "2001-02-03T05:00+01:00", false, false, true),
+            ("2001-02-03T04:00:30Z", "2001-02-03T05:00+01:00", false, false, true),
+            ("2001-02-03T04:00Z", "2001-02-03T04:00+01:00", false, true, false),
+            ("2001-02-03T04:00Z", "2001-02-03T04:00-01:00", true, false, false),
+            // naive dates borrow the other's offset
+            ("2001-02-03T04:00", "2001-02-03T04:00+01:00", false, false, true),
+            ("2001-02-03T04", "2001-02-03T05Z", true, false, false),
+            ("2001-02-03T05", "2001-02-03T05+0100", false, false, true),
+            ("2001-02-03", "2001-02-03T23:59:59-05:00", false, false, false),
+            ("2001-02-03T23:59:59-05:00", "2001-02-03", false, false, true),
+            ("2001-02-04T04:00:00Z", "2001-02-03", false, true, false),
+            ("2001-02-04T04:00:00+05:00", "2001-02-03", false, true, false),
+        ] {
+            let left = Date::from_abs(left).unwrap();
+            let right = Date::from_abs(right).unwrap();
+            assert_eq!(left.before(&right), *before);
+            assert_eq!(left.after(&right), *after);
+            assert_eq!(left.within(&right), *within);
+        }
+    }
+
     #[test]
     fn from_relative_hms() {
         for (input, expect) in &[
@@ -554,10 +1297,10 @@ mod tests {
             ("04:60:06", None),
             ("24:25:06", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -583,16 +1326,17 @@ mod tests {
             ("04::05", None),
             ("04::5", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
     }
 
     #[test]
+    #[allow(clippy::single_element_loop)]
     fn from_relative_hour() {
         for (input, expect) in &[
             // wrap day
@@ -610,10 +1354,10 @@ mod tests {
             // ("4::", None),
             // (":4:", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -660,10 +1404,10 @@ mod tests {
             ("saturdayx", None),
             ("satxurday", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -712,10 +1456,10 @@ mod tests {
             ("32nd", None),
             ("x1st", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -777,10 +1521,10 @@ mod tests {
             ("janu", None),
             ("xjan", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -946,10 +1690,10 @@ mod tests {
             // unhappy path
             (Date::from_abs("2001-02-03T04:05:06"), "1x", None),
         ] {
-            let rel_input = Date::from_rel(input, cf.as_ref().unwrap());
+            let rel_input = Date::from_rel(input, cf.as_ref().unwrap(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, cf.as_ref().unwrap());
+                let any_input = Date::new(input, cf.as_ref().unwrap(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
@@ -967,15 +1711,111 @@ mod tests {
             // unhappy path
             ("then", None),
         ] {
-            let rel_input = Date::from_rel(input, &test_now());
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
+            assert_eq!(rel_input, *expect);
+            if expect.is_some() {
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
+                assert_eq!(any_input, *expect);
+            }
+        }
+    }
+
+    #[test]
+    fn from_relative_period() {
+        // input is relative to Saturday 2001-02-03T04:05:06
+        for (input, expect) in &[
+            ("sow", Date::from_abs("2001-01-29")),
+            ("eow", Date::from_abs("2001-02-04")),
+            ("som", Date::from_abs("2001-02-01")),
+            ("eom", Date::from_abs("2001-02-28")),
+            ("soq", Date::from_abs("2001-01-01")),
+            ("eoq", Date::from_abs("2001-03-31")),
+            ("soy", Date::from_abs("2001-01-01")),
+            ("eoy", Date::from_abs("2001-12-31")),
+            ("start of week", Date::from_abs("2001-01-29")),
+            ("end of month", Date::from_abs("2001-02-28")),
+            ("end-of-quarter", Date::from_abs("2001-03-31")),
+            ("start_of_year", Date::from_abs("2001-01-01")),
+            // unhappy path
+            ("eod", None),
+            ("EOM", None),
+            ("end of", None),
+            ("end of decade", None),
+            ("end of months", None),
+        ] {
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
+            assert_eq!(rel_input, *expect);
+            if expect.is_some() {
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
+                assert_eq!(any_input, *expect);
+            }
+        }
+    }
+
+    #[test]
+    fn from_relative_phrase() {
+        // input is relative to Saturday 2001-02-03T04:05:06
+        for (input, expect) in &[
+            ("next friday", Date::from_abs("2001-02-09")),
+            ("next-fri", Date::from_abs("2001-02-09")),
+            ("next saturday", Date::from_abs("2001-02-10")),
+            ("next mar", Date::from_abs("2001-03")),
+            ("next february", Date::from_abs("2002-02")),
+            ("last friday", Date::from_abs("2001-02-02")),
+            ("last monday", Date::from_abs("2001-01-29")),
+            ("last_saturday", Date::from_abs("2001-01-27")),
+            ("last january", Date::from_abs("2001-01")),
+            ("last feb", Date::from_abs("2000-02")),
+            ("in 2 weeks", Date::from_abs("2001-02-17T04:05:06")),
+            ("in 1 day", Date::from_abs("2001-02-04T04:05:06")),
+            ("in  3   months", Date::from_abs("2001-05-03T04:05:06")),
+            ("in-3-hours", Date::from_abs("2001-02-03T07:05:06")),
+            ("in 2 weekdays", Date::from_abs("2001-02-06T04:05:06")),
+            ("in 2w", Date::from_abs("2001-02-17T04:05:06")),
+            // unhappy path
+            ("next", None),
+            ("next day", None),
+            ("last now", None),
+            ("in", None),
+            ("in two weeks", None),
+            ("in 2 fortnights", None),
+            ("in 2 weeks time", None),
+        ] {
+            let rel_input = Date::from_rel(input, &test_now(), &Calendar::default());
             assert_eq!(rel_input, *expect);
             if expect.is_some() {
-                let any_input = Date::new(input, &test_now());
+                let any_input = Date::new(input, &test_now(), &Calendar::default());
                 assert_eq!(any_input, *expect);
             }
         }
     }
 
+    #[test]
+    fn from_expression() {
+        // input is relative to Saturday 2001-02-03T04:05:06
+        for (input, expect) in &[
+            ("fri+2d", Date::from_abs("2001-02-11")),
+            ("tomorrow-3h", Date::from_abs("2001-02-03T21")),
+            ("now+1d-1h", Date::from_abs("2001-02-04T03:05:06")),
+            ("2001-02-03-1d", Date::from_abs("2001-02-02")),
+            ("2001-02-03T04:00+01:00+1h", Date::from_abs("2001-02-03T05:00+01:00")),
+            ("eom+1d", Date::from_abs("2001-03-01")),
+            ("next-friday+1w", Date::from_abs("2001-02-16")),
+            ("mon+2W", Date::from_abs("2001-02-07")),
+            ("2001-02+30m", Date::from_abs("2001-02-01T00:30")),
+            // unhappy path
+            ("fri+", None),
+            ("fri+2", None),
+            ("fri+2x", None),
+            ("fri+-2d", None),
+            ("x+2d", None),
+            ("due-3d", None),
+        ] {
+            let any_input = Date::new(input, &test_now(), &Calendar::default());
+            assert_eq!(any_input, *expect);
+        }
+    }
+
     #[test]
     fn add() {
         for (start, dur, expect) in &[
@@ -1180,4 +2020,48 @@ mod tests {
             assert_eq!(input.to_string(), *expect);
         }
     }
+
+    #[test]
+    fn to_timestamp() {
+        for (input, expect) in &[
+            ("2001-02-03T04:05:06", "2001-02-03T04:05:06"),
+            ("2001-02-03T04:05", "2001-02-03T04:05:00"),
+            ("2001-02-03T04", "2001-02-03T04:00:00"),
+            ("2001-02-03", "2001-02-03T00:00:00"),
+            ("2001-02", "2001-02-01T00:00:00"),
+            ("2001", "2001-01-01T00:00:00"),
+        ] {
+            assert_eq!(Date::from_abs(input).unwrap().to_timestamp(), *expect);
+        }
+    }
+
+    #[test]
+    fn to_org() {
+        for (input, expect) in &[
+            ("2001-02-03T04:05:06", "2001-02-03 Sat 04:05"),
+            ("2001-02-03T04:05", "2001-02-03 Sat 04:05"),
+            ("2001-02-03T04", "2001-02-03 Sat 04:00"),
+            ("2001-02-03", "2001-02-03 Sat"),
+            ("2001-02", "2001-02-01 Thu"),
+            ("2001", "2001-01-01 Mon"),
+        ] {
+            assert_eq!(Date::from_abs(input).unwrap().to_org(), *expect);
+        }
+    }
+
+    #[test]
+    fn overlaps() {
+        let day = Date::from_abs("2001-02-03").unwrap();
+        for (input, expect) in &[
+            ("2001-02-03T04:05", true),
+            ("2001-02-03", true),
+            ("2001-02", true),
+            ("2001-W05", true),
+            ("2001-02-02T23:59:59", false),
+            ("2001-02-04", false),
+            ("2001-03", false),
+        ] {
+            assert_eq!(Date::from_abs(input).unwrap().overlaps(&day), *expect);
+        }
+    }
 }
@@ -0,0 +1,132 @@
+//
+// Prerequisites between tasks.  A task names itself with an `id:` pair and lists the tasks it
+// waits on in a `depends:` pair, e.g. `depends:design,review`.  Which tasks are still pending is
+// gathered from the task file before filtering, so that filters can tell blocked tasks from ready
+// ones.
+//
+
+use crate::error::*;
+use crate::field::Key;
+use crate::task::Task;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Default)]
+pub struct Dependencies {
+    // Handles of tasks which have not been completed yet
+    pending: HashSet<String>,
+    // Handles which pending tasks depend on
+    depended: HashSet<String>,
+}
+
+impl Dependencies {
+    const ID: &'static str = "id";
+    const DEPENDS: &'static str = "depends";
+    const SEPARATOR: char = ',';
+
+    pub fn new(tasks: &str) -> Dependencies {
+        let mut dependencies = Dependencies::default();
+        for task in tasks
+            .lines()
+            .map(Task::new)
+            .filter(|task| !task.is_completed())
+        {
+            if let Some(id) = Dependencies::id(&task) {
+                dependencies.pending.insert(id.to_owned());
+            }
+            for id in Dependencies::depends(&task) {
+                dependencies.depended.insert(id.to_owned());
+            }
+        }
+        dependencies
+    }
+
+    // A pending task is blocked while any task it depends on is still pending.  Handles which no
+    // longer name a task, such as those of deleted tasks, do not block.
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        !task.is_completed()
+            && Dependencies::depends(task)
+                .iter()
+                .any(|id| self.pending.contains(*id))
+    }
+
+    // Whether the task is pending and other pending tasks depend on it.
+    pub fn is_blocking(&self, task: &Task) -> bool {
+        !task.is_completed() && Dependencies::id(task).is_some_and(|id| self.depended.contains(id))
+    }
+
+    pub fn is_ready(&self, task: &Task) -> bool {
+        !task.is_completed() && !self.is_blocked(task)
+    }
+
+    pub fn id<'t>(task: &'t Task) -> Option<&'t str> {
+        task.get_value(&Key::new(Dependencies::ID))
+            .map(|v| v.as_str())
+            .filter(|id| !id.is_empty())
+    }
+
+    pub fn depends<'t>(task: &'t Task) -> Vec<&'t str> {
+        match task.get_value(&Key::new(Dependencies::DEPENDS)) {
+            Some(v) => v
+                .as_str()
+                .split(Dependencies::SEPARATOR)
+                .filter(|id| !id.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Whether the task is pending and depends on the given handle.
+    pub fn depends_on(task: &Task, id: &str) -> bool {
+        !task.is_completed() && Dependencies::depends(task).contains(&id)
+    }
+
+    // Rejects pending tasks which, through their dependencies, end up depending on themselves.
+    pub fn validate(tasks: &str) -> Result<()> {
+        let tasks = tasks
+            .lines()
+            .map(Task::new)
+            .filter(|task| !task.is_completed())
+            .collect::<Vec<_>>();
+        let graph = tasks
+            .iter()
+            .filter_map(|task| Dependencies::id(task).map(|id| (id, Dependencies::depends(task))))
+            .collect::<HashMap<_, _>>();
+
+        let mut done = HashSet::new();
+        let mut ids = graph.keys().copied().collect::<Vec<_>>();
+        ids.sort_unstable();
+        for id in ids {
+            let mut path = Vec::new();
+            if Dependencies::visit(&graph, id, &mut path, &mut done) {
+                return Err(DependencyCycle(path.join(" -> ")));
+            }
+        }
+        Ok(())
+    }
+
+    // Depth first search; on finding a cycle, leaves just the cycle's handles in `path`.
+    fn visit<'t>(
+        graph: &HashMap<&'t str, Vec<&'t str>>,
+        id: &'t str,
+        path: &mut Vec<&'t str>,
+        done: &mut HashSet<&'t str>,
+    ) -> bool {
+        if let Some(start) = path.iter().position(|p| *p == id) {
+            path.drain(..start);
+            path.push(id);
+            return true;
+        }
+        if done.contains(id) {
+            return false;
+        }
+        path.push(id);
+        for dep in graph.get(id).into_iter().flatten() {
+            if graph.contains_key(dep) && Dependencies::visit(graph, dep, path, done) {
+                return true;
+            }
+        }
+        path.pop();
+        done.insert(id);
+        false
+    }
+}
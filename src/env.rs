@@ -0,0 +1,43 @@
+//
+// What a run consults besides its tasks and arguments: which days are business days, which tasks
+// block others, the dates virtual tags compare against, the urgency weights and how to colour
+// output.  lib.rs works these out once and hands them to the command, which passes them on to
+// filtering, sorting and printing.
+//
+
+use crate::calendar::Calendar;
+use crate::color::Depth;
+use crate::depends::Dependencies;
+use crate::field::Number;
+use crate::tags::TagDates;
+use crate::task::Task;
+use crate::theme::{Paint, Palette, Rule};
+use crate::urgency::Urgency;
+
+pub struct Env<'a> {
+    pub calendar: &'a Calendar,
+    pub dependencies: Dependencies,
+    pub tag_dates: TagDates,
+    pub urgency: Urgency,
+    pub palette: Palette,
+    pub rules: Vec<Rule<'a>>,
+    pub depth: Depth,
+    pub print_color: bool,
+}
+
+impl<'a> Env<'a> {
+    pub fn paint(&self) -> Paint<'_> {
+        Paint::new(self.print_color, self.depth, &self.palette)
+    }
+
+    // Colours for printing the task, in those of the first rule it matches, if any.
+    pub fn paint_task(&self, task: &Task, nr: Number) -> Paint<'_> {
+        self.paint()
+            .with_rule(Rule::find(&self.rules, task, nr, self))
+    }
+
+    pub fn urgency(&self, task: &Task) -> f64 {
+        self.urgency
+            .score(task, &self.dependencies, &self.tag_dates)
+    }
+}
@@ -1,9 +1,11 @@
 use std::path::PathBuf;
 
 pub enum Error {
+    ArgExpectsDateKey(String),
     CannotModNegateKeyValue(String),
     CmdDisallowsMod,
     ConfPromptDeniedErr,
+    DependencyCycle(String),
     DirReadErr(PathBuf, std::io::Error),
     EmptyUndo,
     EnvVarErr(String, std::env::VarError),
@@ -11,18 +13,32 @@ pub enum Error {
     FileReadErr(PathBuf, std::io::Error),
     FileRenameErr(PathBuf, PathBuf, std::io::Error),
     FileWriteErr(PathBuf, std::io::Error),
+    InvalidColumn(String),
+    InvalidCommandArg(String),
     InvalidDefaultFilter(String),
+    InvalidDueSoon(String),
     InvalidEnd(String),
     InvalidEntry(String),
+    InvalidHoliday(String),
     InvalidMod(String),
+    InvalidOption(String),
     InvalidPriority(String),
     InvalidRegex(String),
+    InvalidTheme(String),
+    InvalidTimezone(String),
+    InvalidUrgencyWeight(String),
+    InvalidWeekday(String),
+    InvalidWeekend(String),
     KeyExpectsDateValue(String),
     MalformedUndo(String),
     ModExpectsDateKey(String),
     ModExpectsDateValue(String),
+    ModExpectsNumberKey(String),
+    ModExpectsNumberValue(String),
+    ModExpectsRegexValue(String),
     NotAFilterOrCommand(String),
     OsStrToStrErr(std::ffi::OsString),
+    ReportExpectsListCommand(String),
     UndoMismatch(String),
 }
 pub use Error::*;
@@ -43,9 +59,11 @@ macro_rules! args {
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ArgExpectsDateKey(a) => arg!(f, a, "expects a date key"),
             CannotModNegateKeyValue(a) => arg!(f, a, "cannot modify negated key:value; try -key:"),
             CmdDisallowsMod => writeln!(f, "specified command cannot take modifications"),
             ConfPromptDeniedErr => writeln!(f, "confirmation denied, aborting without changes"),
+            DependencyCycle(a) => arg!(f, a, "is a cycle of task dependencies"),
             DirReadErr(v, e) => args!(f, v, e, "unable to read directory"),
             EmptyUndo => writeln!(f, "undo file is empty or non-existent; cannot undo further"),
             EnvVarErr(v, e) => args!(f, v, e, "unable to read environment variable"),
@@ -53,18 +71,52 @@ impl std::fmt::Debug for Error {
             FileReadErr(v, e) => args!(f, v, e, "unable to read file"),
             FileRenameErr(s, d, e) => writeln!(f, "cannot rename {:?} over {:?}: {}", s, d, e),
             FileWriteErr(v, e) => args!(f, v, e, "unable to write file"),
+            InvalidColumn(a) => arg!(f, a, "is not a valid column"),
+            InvalidCommandArg(a) => arg!(f, a, "is not a valid argument for the command"),
             InvalidDefaultFilter(a) => arg!(f, a, "is an invalid default filter"),
+            InvalidDueSoon(a) => arg!(f, a, "is an invalid due-soon window; expects e.g. 3d"),
             InvalidEnd(a) => arg!(f, a, "is an invalid end date; expects one-day resolution"),
             InvalidEntry(a) => arg!(f, a, "is an invalid entry date; expects one-day resolution"),
+            InvalidHoliday(a) => arg!(
+                f,
+                a,
+                "is not a valid holiday; expects YYYY-MM-DD, MM-DD or a range"
+            ),
             InvalidMod(a) => arg!(f, a, "has an invalid .mod:"),
+            InvalidOption(a) => arg!(
+                f,
+                a,
+                "is not a valid option; expects e.g. --color=never or --color-depth=16"
+            ),
             InvalidPriority(a) => arg!(f, a, "is not a valid priority A-Z"),
             InvalidRegex(a) => arg!(f, a, "starts with a '/' but is not valid regex"),
+            InvalidTheme(a) => arg!(f, a, "is not a valid theme line"),
+            InvalidTimezone(a) => arg!(f, a, "is not a valid timezone; expects a UTC offset"),
+            InvalidUrgencyWeight(a) => arg!(f, a, "is an invalid urgency weight"),
+            InvalidWeekday(a) => arg!(f, a, "is not a valid day of the week"),
+            InvalidWeekend(a) => arg!(
+                f,
+                a,
+                "is not a valid weekend; expects at least one business day"
+            ),
             KeyExpectsDateValue(a) => arg!(f, a, "contains date key but non-date value"),
             MalformedUndo(v) => writeln!(f, "undo file contains non-undo line: `{}`", v),
             ModExpectsDateKey(a) => arg!(f, a, "contains non-date key, conflicting with .mod:"),
             ModExpectsDateValue(a) => arg!(f, a, "contains non-date value, conflicting with .mod:"),
+            ModExpectsNumberKey(a) => {
+                arg!(f, a, "contains non-numeric key, conflicting with .mod:")
+            }
+            ModExpectsNumberValue(a) => {
+                arg!(f, a, "contains non-numeric value, conflicting with .mod:")
+            }
+            ModExpectsRegexValue(a) => arg!(f, a, "contains invalid regex, conflicting with .mod:"),
             NotAFilterOrCommand(a) => arg!(f, a, "is not a valid filter or command"),
             OsStrToStrErr(v) => arg!(f, v, "unable to convert to UTF-8 string"),
+            ReportExpectsListCommand(a) => arg!(
+                f,
+                a,
+                "is a report; expects its filters followed by a command not modifying tasks"
+            ),
             UndoMismatch(v) => writeln!(f, "unable to find `{}` in task file to undo", v),
         }
     }
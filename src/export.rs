@@ -0,0 +1,295 @@
+// Non-interactive output formats intended for consumption by other tools.
+
+use crate::date::Date;
+use crate::error::*;
+use crate::field::*;
+use crate::task::Task;
+use crate::token::Token;
+use std::collections::BTreeSet;
+
+pub enum Format {
+    Csv,
+    Tsv,
+}
+
+impl Format {
+    pub fn push_row<I, S>(&self, stdout: &mut String, fields: I)
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                stdout.push(self.delimiter());
+            }
+            self.push_field(stdout, field.as_ref());
+        }
+        stdout.push('\n');
+    }
+
+    fn delimiter(&self) -> char {
+        match self {
+            Format::Csv => ',',
+            Format::Tsv => '\t',
+        }
+    }
+
+    fn push_field(&self, stdout: &mut String, field: &str) {
+        match self {
+            // RFC 4180: quote fields containing special characters, doubling embedded quotes.
+            Format::Csv if field.contains([',', '"', '\n', '\r']) => {
+                stdout.push('"');
+                stdout.push_str(&field.replace('"', "\"\""));
+                stdout.push('"');
+            }
+            Format::Csv => stdout.push_str(field),
+            // TSV cannot quote; escape the delimiter and line breaks instead.
+            Format::Tsv => {
+                for c in field.chars() {
+                    match c {
+                        '\\' => stdout.push_str("\\\\"),
+                        '\t' => stdout.push_str("\\t"),
+                        '\n' => stdout.push_str("\\n"),
+                        '\r' => stdout.push_str("\\r"),
+                        c => stdout.push(c),
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub enum Checklist {
+    Markdown,
+    Org,
+}
+
+pub struct ChecklistOptions<'a> {
+    scheduled: Option<Key<'a>>,
+    deadline: Option<Key<'a>>,
+    group_by_project: bool,
+}
+
+impl Checklist {
+    pub fn print(&self, stdout: &mut String, tasks: &[Task], options: &ChecklistOptions) {
+        if !options.group_by_project {
+            for task in tasks {
+                self.push_task(stdout, task, options, 1);
+            }
+            return;
+        }
+
+        let projects = tasks
+            .iter()
+            .flat_map(|task| task.iter())
+            .filter_map(|(token, _)| match token {
+                Token::Project(proj) => Some(proj.as_str().to_owned()),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        for proj in &projects {
+            self.push_heading(stdout, proj);
+            for task in tasks
+                .iter()
+                .filter(|task| task.has_token(&Token::Project(Project::new(proj).unwrap())))
+            {
+                self.push_task(stdout, task, options, 2);
+            }
+        }
+
+        let mut unassigned = tasks
+            .iter()
+            .filter(|task| !task.iter().any(|(t, _)| matches!(t, Token::Project(_))))
+            .peekable();
+        if unassigned.peek().is_some() {
+            self.push_heading(stdout, ChecklistOptions::GROUP_NONE);
+            for task in unassigned {
+                self.push_task(stdout, task, options, 2);
+            }
+        }
+    }
+
+    fn push_heading(&self, stdout: &mut String, heading: &str) {
+        match self {
+            Checklist::Markdown => stdout.push_str("## "),
+            Checklist::Org => stdout.push_str("* "),
+        }
+        stdout.push_str(heading);
+        stdout.push('\n');
+    }
+
+    fn push_task(
+        &self,
+        stdout: &mut String,
+        task: &Task,
+        options: &ChecklistOptions,
+        depth: usize,
+    ) {
+        let body = task
+            .iter()
+            .skip_while(|(token, _)| {
+                matches!(
+                    token,
+                    Token::Marker(_)
+                        | Token::End(_)
+                        | Token::Priority(_)
+                        | Token::Entry(_)
+                        | Token::Space(_)
+                )
+            })
+            .take_while(|(token, _)| !matches!(token, Token::Annotation(_)))
+            .collect::<Vec<_>>();
+        let notes = task.get_annotations();
+
+        match self {
+            Checklist::Markdown => {
+                stdout.push_str(match task.is_completed() {
+                    true => "- [x] ",
+                    false => "- [ ] ",
+                });
+                if let Some(pri) = task.get_priority() {
+                    stdout.push('(');
+                    stdout.push(pri.as_u8() as char);
+                    stdout.push_str(") ");
+                }
+                let title = body
+                    .iter()
+                    .filter(|(token, _)| !matches!(token, Token::Space(_)))
+                    .map(|(_, range)| &task.as_str()[range.clone()])
+                    .collect::<Vec<_>>();
+                stdout.push_str(&title.join(" "));
+                stdout.push('\n');
+                for note in notes {
+                    stdout.push_str("  - ");
+                    stdout.push_str(&note);
+                    stdout.push('\n');
+                }
+            }
+            Checklist::Org => {
+                let indent = " ".repeat(depth + 1);
+                stdout.push_str(&"*".repeat(depth));
+                stdout.push_str(match task.is_completed() {
+                    true => " DONE ",
+                    false => " TODO ",
+                });
+                if let Some(pri) = task.get_priority() {
+                    stdout.push_str("[#");
+                    stdout.push(pri.as_u8() as char);
+                    stdout.push_str("] ");
+                }
+                let title = body
+                    .iter()
+                    .filter(|(token, _)| match token {
+                        Token::Normal(_) => true,
+                        Token::Pair(Pair { key, .. }) => {
+                            Some(key) != options.scheduled.as_ref()
+                                && Some(key) != options.deadline.as_ref()
+                        }
+                        _ => false,
+                    })
+                    .map(|(_, range)| &task.as_str()[range.clone()])
+                    .collect::<Vec<_>>();
+                stdout.push_str(&title.join(" "));
+                let tags = task
+                    .iter()
+                    .filter(|(token, _)| matches!(token, Token::Project(_) | Token::Context(_)))
+                    .map(|(_, range)| org_tag(&task.as_str()[range]))
+                    .collect::<Vec<_>>();
+                if !tags.is_empty() {
+                    stdout.push_str(" :");
+                    stdout.push_str(&tags.join(":"));
+                    stdout.push(':');
+                }
+                stdout.push('\n');
+
+                let mut planning = Vec::new();
+                if let Some(end) = task.get_end().and_then(|e| Date::from_abs(e.as_str())) {
+                    planning.push(format!("CLOSED: [{}]", end.to_org()));
+                }
+                for (label, key) in &[
+                    ("SCHEDULED", &options.scheduled),
+                    ("DEADLINE", &options.deadline),
+                ] {
+                    if let Some(date) = key
+                        .as_ref()
+                        .and_then(|key| task.get_value(key))
+                        .and_then(|value| Date::from_abs(value.as_str()))
+                    {
+                        planning.push(format!("{}: <{}>", label, date.to_org()));
+                    }
+                }
+                if !planning.is_empty() {
+                    stdout.push_str(&indent);
+                    stdout.push_str(&planning.join(" "));
+                    stdout.push('\n');
+                }
+                for note in notes {
+                    stdout.push_str(&indent);
+                    stdout.push_str(&note);
+                    stdout.push('\n');
+                }
+            }
+        }
+    }
+}
+
+impl<'a> ChecklistOptions<'a> {
+    const SCHEDULED: &'static str = "scheduled";
+    const DEADLINE: &'static str = "deadline";
+    const GROUP: &'static str = "group";
+
+    const GROUP_PROJECT: &'static str = "project";
+    const GROUP_NONE: &'static str = "none";
+
+    pub fn new(args: &[&'a str], date_keys: &[Key], checklist: &Checklist) -> Result<Self> {
+        let mut options = ChecklistOptions {
+            scheduled: None,
+            deadline: None,
+            group_by_project: false,
+        };
+
+        for arg in args {
+            let (name, value) = match arg.split_once(':') {
+                Some((name, value)) => (name, value),
+                None => return Err(InvalidCommandArg(arg.to_string())),
+            };
+            let is_date_key = date_keys.iter().any(|k| k == &Key::new(value));
+
+            match (name, checklist) {
+                (ChecklistOptions::SCHEDULED, Checklist::Org) if is_date_key => {
+                    options.scheduled = Some(Key::new(value))
+                }
+                (ChecklistOptions::DEADLINE, Checklist::Org) if is_date_key => {
+                    options.deadline = Some(Key::new(value))
+                }
+                (ChecklistOptions::SCHEDULED, Checklist::Org)
+                | (ChecklistOptions::DEADLINE, Checklist::Org) => {
+                    return Err(ArgExpectsDateKey(arg.to_string()))
+                }
+                (ChecklistOptions::GROUP, _) => {
+                    options.group_by_project = match value {
+                        ChecklistOptions::GROUP_PROJECT => true,
+                        ChecklistOptions::GROUP_NONE => false,
+                        _ => return Err(InvalidCommandArg(arg.to_string())),
+                    }
+                }
+                _ => return Err(InvalidCommandArg(arg.to_string())),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+// Org tags may only contain alphanumerics and `_@#%`; drop the project sigil and replace the rest.
+fn org_tag(tag: &str) -> String {
+    tag.strip_prefix('+')
+        .unwrap_or(tag)
+        .chars()
+        .map(|c| match c.is_alphanumeric() || "_@#%".contains(c) {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
@@ -69,6 +69,14 @@ impl<'a> Context<'a> {
     pub fn as_str(&self) -> &str {
         self.0
     }
+
+    // Whether other is this context or, with `.` separating levels, one nested under it.
+    pub fn contains(&self, other: &Context) -> bool {
+        match other.0.strip_prefix(self.0) {
+            Some(rest) => rest.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
+    }
 }
 
 impl<'a> End<'a> {
@@ -117,6 +125,19 @@ impl<'a> Key<'a> {
     pub fn len(&self) -> usize {
         self.0.len()
     }
+
+    // A comma separated list of keys, each of which must be a date key.
+    pub fn new_date_list(str: &'a str, date_keys: &[Key]) -> Option<Vec<Self>> {
+        let mut keys = Vec::new();
+        for str in str.split(',') {
+            let key = Key::new(str);
+            if !date_keys.contains(&key) {
+                return None;
+            }
+            keys.push(key);
+        }
+        Some(keys)
+    }
 }
 
 impl Marker {
@@ -235,14 +256,14 @@ impl Priority {
     }
 
     pub fn from_char(c: char) -> Option<Self> {
-        match ('A'..='Z').contains(&c) {
+        match c.is_ascii_uppercase() {
             true => Some(Priority(c as u8)),
             false => None,
         }
     }
 
     pub fn as_u8(&self) -> u8 {
-        self.0 as u8
+        self.0
     }
 
     pub fn len(&self) -> usize {
@@ -265,6 +286,14 @@ impl<'a> Project<'a> {
     pub fn as_str(&self) -> &str {
         self.0
     }
+
+    // Whether other is this project or, with `.` separating levels, one nested under it.
+    pub fn contains(&self, other: &Project) -> bool {
+        match other.0.strip_prefix(self.0) {
+            Some(rest) => rest.is_empty() || rest.starts_with('.'),
+            None => false,
+        }
+    }
 }
 
 impl<'a> Space<'a> {
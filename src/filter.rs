@@ -1,9 +1,11 @@
+use crate::calendar::Calendar;
 use crate::date::Date;
+use crate::env::Env;
 use crate::error::*;
 use crate::field::*;
-use crate::regex::Regex;
+use crate::regex::{Regex, Scope};
+use crate::tags::VirtualTag;
 use crate::task::Task;
-use crate::token::Token;
 use std::ops::RangeInclusive;
 
 pub struct Filter<'a> {
@@ -14,6 +16,8 @@ pub struct Filter<'a> {
 enum Kind<'a> {
     All,
     IsCompleted,
+    // Virtual tags also match tasks literally tagged with the project of the same name
+    HasVirtualTag(VirtualTag, Project<'a>),
     HasProject(Project<'a>),
     HasContext(Context<'a>),
     HasPair(FilterPair<'a>),
@@ -45,13 +49,45 @@ enum FilterPair<'a> {
     EndIn(Date),
     EntryIn(Date),
     ValueIn(Key<'a>, Date),
+    EndBetween(Option<Date>, Option<Date>),
+    EntryBetween(Option<Date>, Option<Date>),
+    ValueBetween(Key<'a>, Option<Date>, Option<Date>),
+    ValueGreater(Key<'a>, f64),
+    ValueGreaterOrEqual(Key<'a>, f64),
+    ValueLess(Key<'a>, f64),
+    ValueLessOrEqual(Key<'a>, f64),
+    ValueRange(Key<'a>, f64, f64),
+    EndMatches(StrMatch<'a>),
+    EntryMatches(StrMatch<'a>),
+    ValueMatches(Key<'a>, StrMatch<'a>),
+    Annotation(String),
+    AnyAnnotation,
+    NoAnnotation,
+    AnnotationBefore(Date),
+    AnnotationAfter(Date),
+    AnnotationIn(Date),
+    AnnotationBetween(Option<Date>, Option<Date>),
+    AnnotationMatches(StrMatch<'a>),
+}
+
+// Matches against part of a single pair's value rather than the whole task.
+enum StrMatch<'a> {
+    Has(&'a str),
+    StartsWith(&'a str),
+    EndsWith(&'a str),
+    Regex(Box<Regex>),
 }
 
 impl<'a> Filter<'a> {
     const ALL: &'static str = "all";
     const DONE: &'static str = "+done";
 
-    pub fn new(str: &'a str, now: &Date, date_keys: &[Key]) -> Result<Option<Self>> {
+    pub fn new(
+        str: &'a str,
+        now: &Date,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<Option<Self>> {
         let (str, negate) = match str.strip_prefix('-').or_else(|| str.strip_prefix('!')) {
             Some(stripped_str) => (stripped_str, true),
             None => (str, false),
@@ -61,6 +97,8 @@ impl<'a> Filter<'a> {
             Kind::All
         } else if str == Filter::DONE {
             Kind::IsCompleted
+        } else if let (Some(tag), Some(proj)) = (VirtualTag::new(str), Project::new(str)) {
+            Kind::HasVirtualTag(tag, proj)
         } else if let Some(proj) = Project::new(str) {
             Kind::HasProject(proj)
         } else if let Some(ctx) = Context::new(str) {
@@ -75,7 +113,7 @@ impl<'a> Filter<'a> {
             Kind::WithinNumberList(nr_list)
         } else if let Some(nr_range) = Number::new_range(str) {
             Kind::WithinNumberRange(nr_range)
-        } else if let Some(pair) = FilterPair::new(str, now, date_keys)? {
+        } else if let Some(pair) = FilterPair::new(str, now, date_keys, calendar)? {
             Kind::HasPair(pair)
         } else {
             return Ok(None);
@@ -84,12 +122,15 @@ impl<'a> Filter<'a> {
         Ok(Some(Filter { kind, negate }))
     }
 
-    pub fn keep(&self, task: &Task, nr: Number) -> bool {
+    pub fn keep(&self, task: &Task, nr: Number, env: &Env) -> bool {
         let result = match &self.kind {
             Kind::All => true,
             Kind::IsCompleted => task.is_completed(),
-            Kind::HasProject(proj) => task.has_token(&Token::Project(proj.clone())),
-            Kind::HasContext(ctx) => task.has_token(&Token::Context(ctx.clone())),
+            Kind::HasVirtualTag(tag, proj) => {
+                tag.is_set(task, &env.tag_dates, &env.dependencies) || task.has_project(proj)
+            }
+            Kind::HasProject(proj) => task.has_project(proj),
+            Kind::HasContext(ctx) => task.has_context(ctx),
             Kind::HasPair(pair) => match pair {
                 FilterPair::End(v) => task.get_end() == Some(End(v)),
                 FilterPair::Priority(v) => task.get_priority() == Some(*v),
@@ -148,8 +189,89 @@ impl<'a> Filter<'a> {
                     .and_then(|d| Date::from_abs(d.as_str()))
                     .filter(|d| d.within(cf))
                     .is_some(),
+                FilterPair::EndBetween(start, end) => task
+                    .get_end()
+                    .and_then(|d| Date::from_abs(d.as_str()))
+                    .filter(|d| d.between(start.as_ref(), end.as_ref()))
+                    .is_some(),
+                FilterPair::EntryBetween(start, end) => task
+                    .get_entry()
+                    .and_then(|d| Date::from_abs(d.as_str()))
+                    .filter(|d| d.between(start.as_ref(), end.as_ref()))
+                    .is_some(),
+                FilterPair::ValueBetween(k, start, end) => task
+                    .get_value(k)
+                    .and_then(|d| Date::from_abs(d.as_str()))
+                    .filter(|d| d.between(start.as_ref(), end.as_ref()))
+                    .is_some(),
+                FilterPair::ValueGreater(k, cf) => task
+                    .get_value(k)
+                    .and_then(|v| FilterPair::parse_number(v.as_str()))
+                    .filter(|v| v > cf)
+                    .is_some(),
+                FilterPair::ValueGreaterOrEqual(k, cf) => task
+                    .get_value(k)
+                    .and_then(|v| FilterPair::parse_number(v.as_str()))
+                    .filter(|v| v >= cf)
+                    .is_some(),
+                FilterPair::ValueLess(k, cf) => task
+                    .get_value(k)
+                    .and_then(|v| FilterPair::parse_number(v.as_str()))
+                    .filter(|v| v < cf)
+                    .is_some(),
+                FilterPair::ValueLessOrEqual(k, cf) => task
+                    .get_value(k)
+                    .and_then(|v| FilterPair::parse_number(v.as_str()))
+                    .filter(|v| v <= cf)
+                    .is_some(),
+                FilterPair::ValueRange(k, min, max) => task
+                    .get_value(k)
+                    .and_then(|v| FilterPair::parse_number(v.as_str()))
+                    .filter(|v| min <= v && v <= max)
+                    .is_some(),
+                FilterPair::EndMatches(m) => {
+                    task.get_end().filter(|v| m.is_match(v.as_str())).is_some()
+                }
+                FilterPair::EntryMatches(m) => task
+                    .get_entry()
+                    .filter(|v| m.is_match(v.as_str()))
+                    .is_some(),
+                FilterPair::ValueMatches(k, m) => task
+                    .get_value(k)
+                    .filter(|v| m.is_match(v.as_str()))
+                    .is_some(),
+                FilterPair::Annotation(v) => task.get_annotations().iter().any(|a| a == v),
+                FilterPair::AnyAnnotation => !task.get_annotations().is_empty(),
+                FilterPair::NoAnnotation => task.get_annotations().is_empty(),
+                FilterPair::AnnotationBefore(cf) => task
+                    .get_annotations()
+                    .iter()
+                    .filter_map(|a| FilterPair::annotation_date(a))
+                    .any(|d| d.before(cf)),
+                FilterPair::AnnotationAfter(cf) => task
+                    .get_annotations()
+                    .iter()
+                    .filter_map(|a| FilterPair::annotation_date(a))
+                    .any(|d| d.after(cf)),
+                FilterPair::AnnotationIn(cf) => task
+                    .get_annotations()
+                    .iter()
+                    .filter_map(|a| FilterPair::annotation_date(a))
+                    .any(|d| d.within(cf)),
+                FilterPair::AnnotationBetween(start, end) => task
+                    .get_annotations()
+                    .iter()
+                    .filter_map(|a| FilterPair::annotation_date(a))
+                    .any(|d| d.between(start.as_ref(), end.as_ref())),
+                FilterPair::AnnotationMatches(m) => {
+                    task.get_annotations().iter().any(|a| m.is_match(a))
+                }
+            },
+            Kind::MatchesRegex(regex) => match regex.scope() {
+                Scope::Line => regex.is_match(task.as_str()),
+                Scope::Body => regex.is_match(&task.get_body()),
+                Scope::Annotations => task.get_annotations().iter().any(|a| regex.is_match(a)),
             },
-            Kind::MatchesRegex(regex) => regex.is_match(task.as_str()),
             Kind::WithinPriorityRange(range) => task
                 .get_priority()
                 .filter(|pri| range.contains(pri))
@@ -169,6 +291,7 @@ impl<'a> Filter<'a> {
             (Kind::All, _) => true,
             (_, Kind::All) => true,
             (Kind::IsCompleted, Kind::IsCompleted) => true,
+            (Kind::HasVirtualTag(a, _), Kind::HasVirtualTag(b, _)) => a.conflicts(b),
             (Kind::HasProject(a), Kind::HasProject(b)) => a == b,
             (Kind::HasContext(a), Kind::HasContext(b)) => a == b,
             (Kind::HasPair(a), Kind::HasPair(b)) => a.stringify_key() == b.stringify_key(),
@@ -186,14 +309,32 @@ impl<'a> FilterPair<'a> {
     const KEY_END: &'static str = "end";
     const KEY_PRI: &'static str = "pri";
     const KEY_ENTRY: &'static str = "entry";
+    const KEY_ANNOTATION: &'static str = "annotation";
 
     const MOD_ANY: &'static str = "any";
     const MOD_NONE: &'static str = "none";
     const MOD_BEFORE: &'static str = "before";
     const MOD_AFTER: &'static str = "after";
     const MOD_IN: &'static str = "in";
+    const MOD_BETWEEN: &'static str = "between";
+    const MOD_GT: &'static str = "gt";
+    const MOD_GTE: &'static str = "gte";
+    const MOD_LT: &'static str = "lt";
+    const MOD_LTE: &'static str = "lte";
+    const MOD_RANGE: &'static str = "range";
+    const MOD_HAS: &'static str = "has";
+    const MOD_STARTSWITH: &'static str = "startswith";
+    const MOD_ENDSWITH: &'static str = "endswith";
+    const MOD_REGEX: &'static str = "regex";
+
+    const RANGE: &'static str = "..";
 
-    pub fn new(str: &'a str, now: &Date, date_keys: &[Key]) -> Result<Option<Self>> {
+    pub fn new(
+        str: &'a str,
+        now: &Date,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<Option<Self>> {
         let (key, xmod, value) = match str.split_once(':') {
             None => return Ok(None),
             Some((key_mod, value)) => match key_mod.split_once('.') {
@@ -217,6 +358,9 @@ impl<'a> FilterPair<'a> {
                 }
             }
             (FilterPair::KEY_ENTRY, None) => FilterPair::Entry(value.to_owned()),
+            (FilterPair::KEY_ANNOTATION, xmod) => {
+                FilterPair::new_annotation(str, xmod, value, now, calendar)?
+            }
             (_, None) => FilterPair::KeyValue(Key::new(key), value.to_owned()),
             (FilterPair::KEY_END, Some(FilterPair::MOD_ANY)) => FilterPair::AnyEnd,
             (FilterPair::KEY_PRI, Some(FilterPair::MOD_ANY)) => FilterPair::AnyPriority,
@@ -226,64 +370,227 @@ impl<'a> FilterPair<'a> {
             (FilterPair::KEY_PRI, Some(FilterPair::MOD_NONE)) => FilterPair::NoPriority,
             (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_NONE)) => FilterPair::NoEntry,
             (_, Some(FilterPair::MOD_NONE)) => FilterPair::NoKey(Key::new(key)),
-            (FilterPair::KEY_END, Some(FilterPair::MOD_BEFORE)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EndBefore(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_END, Some(FilterPair::MOD_BEFORE)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EndBefore(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (FilterPair::KEY_PRI, Some(FilterPair::MOD_BEFORE)) => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_BEFORE)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EntryBefore(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_BEFORE)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EntryBefore(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (_, Some(FilterPair::MOD_BEFORE)) if !is_date_key => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (_, Some(FilterPair::MOD_BEFORE)) => match Date::new(value, now) {
+            (_, Some(FilterPair::MOD_BEFORE)) => match Date::new(value, now, calendar) {
                 Some(date) => FilterPair::ValueBefore(Key::new(key), date),
                 None => return Err(ModExpectsDateValue(str.to_string())),
             },
-            (FilterPair::KEY_END, Some(FilterPair::MOD_AFTER)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EndAfter(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_END, Some(FilterPair::MOD_AFTER)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EndAfter(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (FilterPair::KEY_PRI, Some(FilterPair::MOD_AFTER)) => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_AFTER)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EntryAfter(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_AFTER)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EntryAfter(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (_, Some(FilterPair::MOD_AFTER)) if !is_date_key => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (_, Some(FilterPair::MOD_AFTER)) => match Date::new(value, now) {
+            (_, Some(FilterPair::MOD_AFTER)) => match Date::new(value, now, calendar) {
                 Some(date) => FilterPair::ValueAfter(Key::new(key), date),
                 None => return Err(ModExpectsDateValue(str.to_string())),
             },
-            (FilterPair::KEY_END, Some(FilterPair::MOD_IN)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EndIn(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_END, Some(FilterPair::MOD_IN)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EndIn(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (FilterPair::KEY_PRI, Some(FilterPair::MOD_IN)) => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_IN)) => match Date::new(value, now) {
-                Some(date) => FilterPair::EntryIn(date),
-                None => return Err(ModExpectsDateValue(str.to_string())),
-            },
+            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_IN)) => {
+                match Date::new(value, now, calendar) {
+                    Some(date) => FilterPair::EntryIn(date),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
             (_, Some(FilterPair::MOD_IN)) if !is_date_key => {
                 return Err(ModExpectsDateKey(str.to_string()))
             }
-            (_, Some(FilterPair::MOD_IN)) => match Date::new(value, now) {
+            (_, Some(FilterPair::MOD_IN)) => match Date::new(value, now, calendar) {
                 Some(date) => FilterPair::ValueIn(Key::new(key), date),
                 None => return Err(ModExpectsDateValue(str.to_string())),
             },
+            (FilterPair::KEY_END, Some(FilterPair::MOD_BETWEEN)) => {
+                match FilterPair::new_date_range(value, now, calendar) {
+                    Some((start, end)) => FilterPair::EndBetween(start, end),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
+            (FilterPair::KEY_PRI, Some(FilterPair::MOD_BETWEEN)) => {
+                return Err(ModExpectsDateKey(str.to_string()))
+            }
+            (FilterPair::KEY_ENTRY, Some(FilterPair::MOD_BETWEEN)) => {
+                match FilterPair::new_date_range(value, now, calendar) {
+                    Some((start, end)) => FilterPair::EntryBetween(start, end),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
+            (_, Some(FilterPair::MOD_BETWEEN)) if !is_date_key => {
+                return Err(ModExpectsDateKey(str.to_string()))
+            }
+            (_, Some(FilterPair::MOD_BETWEEN)) => {
+                match FilterPair::new_date_range(value, now, calendar) {
+                    Some((start, end)) => FilterPair::ValueBetween(Key::new(key), start, end),
+                    None => return Err(ModExpectsDateValue(str.to_string())),
+                }
+            }
+            (FilterPair::KEY_END, Some(m))
+            | (FilterPair::KEY_PRI, Some(m))
+            | (FilterPair::KEY_ENTRY, Some(m))
+                if FilterPair::is_numeric_mod(m) =>
+            {
+                return Err(ModExpectsNumberKey(str.to_string()))
+            }
+            (_, Some(m)) if is_date_key && FilterPair::is_numeric_mod(m) => {
+                return Err(ModExpectsNumberKey(str.to_string()))
+            }
+            (_, Some(FilterPair::MOD_RANGE)) => match FilterPair::new_number_range(value) {
+                Some((min, max)) => FilterPair::ValueRange(Key::new(key), min, max),
+                None => return Err(ModExpectsNumberValue(str.to_string())),
+            },
+            (_, Some(m)) if FilterPair::is_numeric_mod(m) => {
+                let key = Key::new(key);
+                match (m, FilterPair::parse_number(value)) {
+                    (_, None) => return Err(ModExpectsNumberValue(str.to_string())),
+                    (FilterPair::MOD_GT, Some(v)) => FilterPair::ValueGreater(key, v),
+                    (FilterPair::MOD_GTE, Some(v)) => FilterPair::ValueGreaterOrEqual(key, v),
+                    (FilterPair::MOD_LT, Some(v)) => FilterPair::ValueLess(key, v),
+                    (_, Some(v)) => FilterPair::ValueLessOrEqual(key, v),
+                }
+            }
+            (FilterPair::KEY_END, Some(m)) if StrMatch::is_mod(m) => {
+                FilterPair::EndMatches(StrMatch::new(str, m, value)?)
+            }
+            (FilterPair::KEY_ENTRY, Some(m)) if StrMatch::is_mod(m) => {
+                FilterPair::EntryMatches(StrMatch::new(str, m, value)?)
+            }
+            (FilterPair::KEY_PRI, Some(m)) if StrMatch::is_mod(m) => {
+                return Err(InvalidMod(str.to_string()))
+            }
+            (_, Some(m)) if StrMatch::is_mod(m) => {
+                FilterPair::ValueMatches(Key::new(key), StrMatch::new(str, m, value)?)
+            }
             (_, Some(_)) => return Err(InvalidMod(str.to_string())),
         }))
     }
 
+    // Annotations are matched individually: the filter holds if any one of them matches.
+    fn new_annotation(
+        str: &'a str,
+        xmod: Option<&str>,
+        value: &'a str,
+        now: &Date,
+        calendar: &Calendar,
+    ) -> Result<Self> {
+        Ok(match xmod {
+            None => FilterPair::Annotation(value.to_owned()),
+            Some(FilterPair::MOD_ANY) => FilterPair::AnyAnnotation,
+            Some(FilterPair::MOD_NONE) => FilterPair::NoAnnotation,
+            Some(FilterPair::MOD_BEFORE) => match Date::new(value, now, calendar) {
+                Some(date) => FilterPair::AnnotationBefore(date),
+                None => return Err(ModExpectsDateValue(str.to_string())),
+            },
+            Some(FilterPair::MOD_AFTER) => match Date::new(value, now, calendar) {
+                Some(date) => FilterPair::AnnotationAfter(date),
+                None => return Err(ModExpectsDateValue(str.to_string())),
+            },
+            Some(FilterPair::MOD_IN) => match Date::new(value, now, calendar) {
+                Some(date) => FilterPair::AnnotationIn(date),
+                None => return Err(ModExpectsDateValue(str.to_string())),
+            },
+            Some(FilterPair::MOD_BETWEEN) => match FilterPair::new_date_range(value, now, calendar)
+            {
+                Some((start, end)) => FilterPair::AnnotationBetween(start, end),
+                None => return Err(ModExpectsDateValue(str.to_string())),
+            },
+            Some(m) if FilterPair::is_numeric_mod(m) => {
+                return Err(ModExpectsNumberKey(str.to_string()))
+            }
+            Some(m) if StrMatch::is_mod(m) => {
+                FilterPair::AnnotationMatches(StrMatch::new(str, m, value)?)
+            }
+            Some(_) => return Err(InvalidMod(str.to_string())),
+        })
+    }
+
+    // The date an annotation was written, if it leads with one as Chore's own annotations do.
+    fn annotation_date(annotation: &str) -> Option<Date> {
+        annotation
+            .split_whitespace()
+            .next()
+            .and_then(Date::from_abs)
+    }
+
+    fn is_numeric_mod(xmod: &str) -> bool {
+        matches!(
+            xmod,
+            FilterPair::MOD_GT
+                | FilterPair::MOD_GTE
+                | FilterPair::MOD_LT
+                | FilterPair::MOD_LTE
+                | FilterPair::MOD_RANGE
+        )
+    }
+
+    // Integers or decimals; anything else, including infinities and NaN, is not a number.
+    fn parse_number(str: &str) -> Option<f64> {
+        str.parse::<f64>().ok().filter(|n| n.is_finite())
+    }
+
+    // Parses `min-max`, where either bound may itself be negative, e.g. `-5--1`.
+    fn new_number_range(value: &str) -> Option<(f64, f64)> {
+        let i = value.get(1..)?.find('-')? + 1;
+        let a = FilterPair::parse_number(&value[..i])?;
+        let b = FilterPair::parse_number(&value[i + 1..])?;
+        match a <= b {
+            true => Some((a, b)),
+            false => Some((b, a)),
+        }
+    }
+
+    // Parses `start..end` where either, but not both, of the bounds may be omitted.
+    fn new_date_range(
+        value: &str,
+        now: &Date,
+        calendar: &Calendar,
+    ) -> Option<(Option<Date>, Option<Date>)> {
+        let (start, end) = value.split_once(FilterPair::RANGE)?;
+        let parse = |str: &str| match str.is_empty() {
+            true => Some(None),
+            false => Date::new(str, now, calendar).map(Some),
+        };
+        match (parse(start)?, parse(end)?) {
+            (None, None) => None,
+            (start, end) => Some((start, end)),
+        }
+    }
+
     fn stringify_key(&self) -> &str {
         match self {
             FilterPair::End(_) => FilterPair::KEY_END,
@@ -292,6 +599,7 @@ impl<'a> FilterPair<'a> {
             FilterPair::EndBefore(_) => FilterPair::KEY_END,
             FilterPair::EndAfter(_) => FilterPair::KEY_END,
             FilterPair::EndIn(_) => FilterPair::KEY_END,
+            FilterPair::EndBetween(..) => FilterPair::KEY_END,
 
             FilterPair::Priority(_) => FilterPair::KEY_PRI,
             FilterPair::AnyPriority => FilterPair::KEY_PRI,
@@ -303,6 +611,7 @@ impl<'a> FilterPair<'a> {
             FilterPair::EntryBefore(_) => FilterPair::KEY_ENTRY,
             FilterPair::EntryAfter(_) => FilterPair::KEY_ENTRY,
             FilterPair::EntryIn(_) => FilterPair::KEY_ENTRY,
+            FilterPair::EntryBetween(..) => FilterPair::KEY_ENTRY,
 
             FilterPair::KeyValue(k, _) => k.as_str(),
             FilterPair::AnyValue(k) => k.as_str(),
@@ -310,6 +619,57 @@ impl<'a> FilterPair<'a> {
             FilterPair::ValueBefore(k, _) => k.as_str(),
             FilterPair::ValueAfter(k, _) => k.as_str(),
             FilterPair::ValueIn(k, _) => k.as_str(),
+            FilterPair::ValueBetween(k, ..) => k.as_str(),
+            FilterPair::ValueGreater(k, _) => k.as_str(),
+            FilterPair::ValueGreaterOrEqual(k, _) => k.as_str(),
+            FilterPair::ValueLess(k, _) => k.as_str(),
+            FilterPair::ValueLessOrEqual(k, _) => k.as_str(),
+            FilterPair::ValueRange(k, ..) => k.as_str(),
+            FilterPair::EndMatches(_) => FilterPair::KEY_END,
+            FilterPair::EntryMatches(_) => FilterPair::KEY_ENTRY,
+            FilterPair::ValueMatches(k, _) => k.as_str(),
+
+            FilterPair::Annotation(_) => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnyAnnotation => FilterPair::KEY_ANNOTATION,
+            FilterPair::NoAnnotation => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnnotationBefore(_) => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnnotationAfter(_) => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnnotationIn(_) => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnnotationBetween(..) => FilterPair::KEY_ANNOTATION,
+            FilterPair::AnnotationMatches(_) => FilterPair::KEY_ANNOTATION,
+        }
+    }
+}
+
+impl<'a> StrMatch<'a> {
+    fn is_mod(xmod: &str) -> bool {
+        matches!(
+            xmod,
+            FilterPair::MOD_HAS
+                | FilterPair::MOD_STARTSWITH
+                | FilterPair::MOD_ENDSWITH
+                | FilterPair::MOD_REGEX
+        )
+    }
+
+    fn new(str: &str, xmod: &str, value: &'a str) -> Result<Self> {
+        Ok(match xmod {
+            FilterPair::MOD_HAS => StrMatch::Has(value),
+            FilterPair::MOD_STARTSWITH => StrMatch::StartsWith(value),
+            FilterPair::MOD_ENDSWITH => StrMatch::EndsWith(value),
+            _ => match Regex::from_pattern(value) {
+                Some(regex) => StrMatch::Regex(Box::new(regex)),
+                None => return Err(ModExpectsRegexValue(str.to_string())),
+            },
+        })
+    }
+
+    fn is_match(&self, value: &str) -> bool {
+        match self {
+            StrMatch::Has(s) => value.contains(s),
+            StrMatch::StartsWith(s) => value.starts_with(s),
+            StrMatch::EndsWith(s) => value.ends_with(s),
+            StrMatch::Regex(regex) => regex.is_match(value),
         }
     }
 }
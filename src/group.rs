@@ -0,0 +1,101 @@
+//
+// Sections of a listing sharing a project, context, priority or key's value.  A task appears in
+// the section of each of its projects or contexts, and in a catch-all section if it has none.
+//
+
+use crate::date::Date;
+use crate::field::*;
+use crate::sort::SortKey;
+use crate::task::Task;
+use crate::token::Token;
+use std::cmp::Ordering;
+
+pub enum Group {
+    Project,
+    Context,
+    Priority,
+    // Dates are grouped by the day they fall on, while weeks, months and the like keep sections of
+    // their own, placed by when they start
+    Date(String),
+    Value(String),
+}
+
+impl Group {
+    const PROJECT: &'static str = "project";
+    const CONTEXT: &'static str = "context";
+    const PRI: &'static str = "pri";
+
+    pub const NONE: &'static str = "none";
+
+    pub fn new(str: &str, date_keys: &[Key]) -> Option<Group> {
+        Some(match str {
+            "" => return None,
+            Group::PROJECT => Group::Project,
+            Group::CONTEXT => Group::Context,
+            Group::PRI => Group::Priority,
+            _ if date_keys.contains(&Key::new(str)) => Group::Date(str.to_owned()),
+            _ => Group::Value(str.to_owned()),
+        })
+    }
+
+    // Headings of the sections the task belongs in, or none for the catch-all section.
+    pub fn headings(&self, task: &Task) -> Vec<String> {
+        let mut headings = Vec::new();
+        match self {
+            Group::Project | Group::Context => {
+                for (token, _) in task.iter() {
+                    let heading = match (self, token) {
+                        (Group::Project, Token::Project(p)) => p.as_str().to_owned(),
+                        (Group::Context, Token::Context(c)) => c.as_str().to_owned(),
+                        _ => continue,
+                    };
+                    if !headings.contains(&heading) {
+                        headings.push(heading);
+                    }
+                }
+            }
+            Group::Priority => {
+                if let Some(pri) = task.get_priority() {
+                    headings.push(format!("({})", pri.as_u8() as char));
+                }
+            }
+            Group::Date(key) => {
+                if let Some(value) = task.get_value(&Key::new(key)) {
+                    let day = Date::from_abs(value.as_str()).map(|date| date.to_day().to_string());
+                    headings.push(format!(
+                        "{}:{}",
+                        key,
+                        day.as_deref().unwrap_or(value.as_str())
+                    ));
+                }
+            }
+            Group::Value(key) => {
+                if let Some(value) = task.get_value(&Key::new(key)) {
+                    headings.push(format!("{}:{}", key, value.as_str()));
+                }
+            }
+        }
+        headings
+    }
+
+    // Orders headings of the same kind, numerically where key values are numbers and
+    // chronologically where they are dates.  Values which are not dates follow those which are.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Group::Date(key) => {
+                let date = |str: &str| str.get(key.len() + 1..).and_then(Date::from_abs);
+                match (date(a), date(b)) {
+                    (Some(a), Some(b)) => a.compare(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => a.cmp(b),
+                }
+            }
+            Group::Value(key) => {
+                let value = |str: &'_ str| str.get(key.len() + 1..).unwrap_or("").to_owned();
+                SortKey::compare_values(&value(a), &value(b))
+            }
+            _ => a.cmp(b),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//
+// Dates shown relative to now, e.g. `in 3d`, `2w ago` or `overdue 1d`, in place of or beside the
+// text stored in the task.  Only the display changes; the task is left as written.
+//
+
+use crate::date::Date;
+use crate::field::*;
+use crate::print::*;
+use crate::theme::{Paint, Part};
+use crate::token::Token;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DateDisplay {
+    Stored,
+    Relative,
+    Both,
+}
+
+impl DateDisplay {
+    const STORED: &'static str = "stored";
+    const RELATIVE: &'static str = "relative";
+    const BOTH: &'static str = "both";
+
+    // Past values of this key are overdue, rather than merely ago, on pending tasks.
+    const KEY_DUE: &'static str = "due";
+
+    pub fn new(str: &str) -> Option<Self> {
+        match str {
+            DateDisplay::STORED => Some(DateDisplay::Stored),
+            DateDisplay::RELATIVE => Some(DateDisplay::Relative),
+            DateDisplay::BOTH => Some(DateDisplay::Both),
+            _ => None,
+        }
+    }
+
+    // Prints the token, rendering entry and end dates and date key values as chosen.
+    pub fn print_token(
+        &self,
+        stdout: &mut String,
+        token: &Token,
+        pending: bool,
+        now: &Date,
+        date_keys: &[Key],
+        paint: Paint,
+    ) {
+        let (stored, key) = match token {
+            Token::Entry(entry) => (entry.as_str(), None),
+            Token::End(end) => (end.as_str(), None),
+            Token::Pair(pair) if date_keys.contains(&pair.key) => {
+                (pair.value.as_str(), Some(&pair.key))
+            }
+            _ => return token.print(stdout, paint),
+        };
+        let date = match (self, Date::from_abs(stored)) {
+            (DateDisplay::Stored, _) | (_, None) => return token.print(stdout, paint),
+            (_, Some(date)) => date,
+        };
+
+        let overdue = pending && key.is_some_and(|key| key.as_str() == DateDisplay::KEY_DUE);
+        match (token, self) {
+            (Token::Pair(pair), DateDisplay::Both) => pair.print(stdout, paint),
+            (Token::Pair(pair), _) => pair.key.print(stdout, paint),
+            (token, DateDisplay::Both) => token.print(stdout, paint),
+            _ => {}
+        }
+        if *self == DateDisplay::Both {
+            paint.fg(Part::Separator).print(stdout, paint);
+            stdout.push_str(" [");
+        }
+        let (text, part) = DateDisplay::describe(&date, now, overdue);
+        paint.fg(part).print(stdout, paint);
+        stdout.push_str(&text);
+        if *self == DateDisplay::Both {
+            paint.fg(Part::Separator).print(stdout, paint);
+            stdout.push(']');
+        }
+    }
+
+    // The date relative to now and the part of the theme it is coloured as.  Only dates already
+    // over are overdue.
+    fn describe(date: &Date, now: &Date, overdue: bool) -> (String, Part) {
+        match (date.days_from(now), overdue && date.before(now)) {
+            (d, true) => (format!("overdue {}", Date::span(d)), Part::Overdue),
+            (0.0, false) => (date.current_name().to_owned(), Part::Current),
+            (d, false) if d > 0.0 => (format!("in {}", Date::span(d)), Part::Upcoming),
+            (d, false) => (format!("{} ago", Date::span(d)), Part::Past),
+        }
+    }
+}
@@ -2,19 +2,33 @@
 // Public interface for the core logic.  Entry point for both main.rs and integration tests.
 //
 
+mod agenda;
 mod args;
+mod calendar;
 mod color;
+mod column;
 mod command;
 mod date;
+mod depends;
+mod env;
 mod error;
+mod export;
 mod field;
 mod filter;
+mod group;
+mod humanize;
 mod modification;
+mod month;
 mod print;
 mod regex;
+mod sort;
+mod table;
+mod tags;
+mod theme;
 mod task;
 mod taskiter;
 mod token;
+mod urgency;
 use crate::args::{ArgIter, ArgNext};
 pub use error::*;
 
@@ -22,14 +36,31 @@ pub use error::*;
 pub struct Config {
     pub args: Vec<String>,
     pub now: chrono::NaiveDateTime,
+    pub utc_offset: chrono::FixedOffset,
+    pub timezone: Option<String>,
+    pub theme: Option<String>,
+    pub urgency: Option<String>,
     pub tasks: Option<String>,
     pub undo: Option<String>,
     pub date_keys: Option<String>,
+    pub due_soon: Option<String>,
+    pub weekend: Option<String>,
+    pub holidays: Vec<File>,
     pub filter_aliases: Vec<File>,
     pub command_aliases: Vec<File>,
     pub modification_aliases: Vec<File>,
     pub default_filters: Vec<File>,
-    pub print_color: bool,
+    pub reports: Vec<File>,
+    // Whether stdout is a terminal
+    pub is_terminal: bool,
+    // Set and non-empty `NO_COLOR`
+    pub no_color: bool,
+    // Set and non-zero `CLICOLOR_FORCE`
+    pub clicolor_force: bool,
+    pub term: Option<String>,
+    pub colorterm: Option<String>,
+    // Columns of the terminal printed to, if known
+    pub width: Option<usize>,
 }
 
 impl Default for Config {
@@ -37,14 +68,27 @@ impl Default for Config {
         Config {
             args: Vec::new(),
             now: chrono::Local::now().naive_local(),
+            utc_offset: *chrono::Local::now().offset(),
+            timezone: None,
+            theme: None,
+            urgency: None,
             tasks: None,
             undo: None,
             date_keys: None,
+            due_soon: None,
+            weekend: None,
+            holidays: Vec::new(),
             filter_aliases: Vec::new(),
             command_aliases: Vec::new(),
             modification_aliases: Vec::new(),
             default_filters: Vec::new(),
-            print_color: false,
+            reports: Vec::new(),
+            is_terminal: false,
+            no_color: false,
+            clicolor_force: false,
+            term: None,
+            colorterm: None,
+            width: None,
         }
     }
 }
@@ -69,7 +113,25 @@ pub enum Output {
 }
 
 pub fn run(config: Config) -> Result<Output> {
-    let now = date::Date::from_chrono(&config.now);
+    let (color, args) = color::ColorConfig::new(
+        &config.args,
+        config.is_terminal,
+        config.no_color,
+        config.clicolor_force,
+        config.term.as_deref(),
+        config.colorterm.as_deref(),
+    )?;
+    let calendar = calendar::Calendar::new(config.weekend.as_deref(), &config.holidays)?;
+    let now = date::Date::from_chrono(&config.now).with_offset(config.utc_offset);
+    // Values written from the CLI are naive unless a timezone is configured, though now keeps the
+    // local offset for comparisons against dates which carry one.
+    let (now, written_now) = match config.timezone.as_deref().map(str::trim) {
+        Some(tz) if !tz.is_empty() => match date::Date::parse_offset(tz) {
+            Some(zone) => (now.to_offset(zone), now.to_offset(zone)),
+            None => return Err(InvalidTimezone(tz.to_owned())),
+        },
+        _ => (now.clone(), now.without_offset()),
+    };
     let date_keys = match &config.date_keys.as_ref() {
         Some(date_keys) => date_keys
             .lines()
@@ -77,28 +139,36 @@ pub fn run(config: Config) -> Result<Output> {
                 Some(str) => str,
                 None => str,
             })
-            .map(|str| field::Key::new(str))
+            .map(field::Key::new)
             .collect::<Vec<_>>(),
         None => Vec::new(),
     };
+    let tag_dates = tags::TagDates::new(&now, config.due_soon.as_deref(), &date_keys, &calendar)?;
+    let urgency = urgency::Urgency::new(&now, config.urgency.as_deref())?;
+    let theme = theme::Theme::new(config.theme.as_deref(), &now, &date_keys, &calendar)?;
 
     let args = ArgIter::new(
-        &config.args,
+        args,
         &now,
+        &written_now,
         &date_keys,
+        &calendar,
         &config.filter_aliases,
         &config.command_aliases,
         &config.modification_aliases,
+        &config.reports,
     );
 
     let mut filters = Vec::new();
     let mut command = command::Command::ListTasks;
     let mut mods = Vec::new();
+    let mut command_args = Vec::new();
 
     for arg in args {
         match arg? {
             ArgNext::Filter(filter) => filters.push(filter),
             ArgNext::Command(new_command) => command = new_command,
+            ArgNext::CommandArg(arg) => command_args.push(arg),
             ArgNext::Modification(modification) => mods.push(modification),
         }
     }
@@ -106,7 +176,7 @@ pub fn run(config: Config) -> Result<Output> {
     let mut default_filters = Vec::new();
     for File { content, .. } in &config.default_filters {
         for str in content.split_ascii_whitespace() {
-            let filter = match filter::Filter::new(&str, &now, &date_keys)? {
+            let filter = match filter::Filter::new(str, &now, &date_keys, &calendar)? {
                 Some(filter) => filter,
                 None => return Err(InvalidDefaultFilter(str.to_owned())),
             };
@@ -119,6 +189,31 @@ pub fn run(config: Config) -> Result<Output> {
 
     let tasks = config.tasks.unwrap_or_else(|| "".to_owned());
     let undo = config.undo.unwrap_or_else(|| "".to_owned());
+    let env = env::Env {
+        calendar: &calendar,
+        dependencies: depends::Dependencies::new(&tasks),
+        tag_dates,
+        urgency,
+        palette: theme.palette,
+        rules: theme.rules,
+        depth: color.depth,
+        print_color: color.print_color,
+    };
 
-    command.run(tasks, undo, &filters, &mods, &date_keys, config.print_color)
+    let output = command.run(
+        tasks,
+        undo,
+        &filters,
+        &mods,
+        &command_args,
+        &date_keys,
+        &config.reports,
+        &now,
+        config.width,
+        &env,
+    )?;
+    if let Output::WriteFiles { tasks, .. } = &output {
+        depends::Dependencies::validate(tasks)?;
+    }
+    Ok(output)
 }
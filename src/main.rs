@@ -16,17 +16,33 @@ macro_rules! path {
 fn main() -> Result<()> {
     let home = std::env::var("HOME").map_err(|e| EnvVarErr("HOME".to_string(), e))?;
 
+    let now = chrono::Local::now();
+
     let config = Config {
         args: std::env::args().skip(1).collect::<Vec<String>>(),
-        now: chrono::Local::now().naive_local(),
+        now: now.naive_local(),
+        utc_offset: *now.offset(),
+        timezone: io::read_file(path!(home, "timezone"))?,
+        theme: io::read_file(path!(home, "theme"))?,
+        urgency: io::read_file(path!(home, "urgency"))?,
         tasks: io::read_file(path!(home, "tasks"))?,
         undo: io::read_file(path!(home, "undo"))?,
         date_keys: io::read_file(path!(home, "date-keys"))?,
+        due_soon: io::read_file(path!(home, "due-soon"))?,
+        weekend: io::read_file(path!(home, "weekend"))?,
+        holidays: io::read_dir(path!(home, "holidays"))?,
         filter_aliases: io::read_dir(path!(home, "filter-aliases"))?,
         command_aliases: io::read_dir(path!(home, "command-aliases"))?,
         modification_aliases: io::read_dir(path!(home, "modification-aliases"))?,
         default_filters: io::read_dir(path!(home, "default-filters"))?,
-        print_color: unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 },
+        reports: io::read_dir(path!(home, "reports"))?,
+        is_terminal: unsafe { libc::isatty(libc::STDOUT_FILENO) == 1 },
+        no_color: std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+        clicolor_force: std::env::var_os("CLICOLOR_FORCE")
+            .is_some_and(|v| !v.is_empty() && v != "0"),
+        term: std::env::var("TERM").ok(),
+        colorterm: std::env::var("COLORTERM").ok(),
+        width: io::terminal_width(),
     };
 
     match chore::run(config).map_err(ChoreErr)? {
@@ -141,13 +157,27 @@ mod io {
         let mut stdout = std::io::BufWriter::with_capacity(str.len(), stdout);
         match stdout.write_all(str.as_bytes()) {
             Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
-            Err(e) => return Err(e).map_err(err),
+            Err(e) => return Err(err(e)),
             Ok(_) => {}
         }
         stdout.flush().map_err(err)?;
         Ok(())
     }
 
+    // Columns of the terminal stdout is connected to, if it is one.
+    pub fn terminal_width() -> Option<usize> {
+        let mut size = libc::winsize {
+            ws_row: 0,
+            ws_col: 0,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        };
+        match unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) } {
+            0 if size.ws_col > 0 => Some(size.ws_col.into()),
+            _ => None,
+        }
+    }
+
     pub fn prompt() -> Result<bool> {
         print("apply changes? [y/N] ")?;
         Ok(matches!(
@@ -1,3 +1,4 @@
+use crate::calendar::Calendar;
 use crate::date::Date;
 use crate::error::*;
 use crate::field::*;
@@ -25,6 +26,14 @@ pub enum ModPair<'a> {
     RemovePriority,
     RemoveEntry,
     RemoveKey(Key<'a>),
+    // Sets a date relative to another date on the same task, e.g. `wait:due-3d`.
+    SetDateFrom(DateField<'a>, DateField<'a>, &'a str),
+}
+
+pub enum DateField<'a> {
+    End,
+    Entry,
+    Value(Key<'a>),
 }
 
 pub struct ModOutput {
@@ -35,7 +44,12 @@ pub struct ModOutput {
 impl<'a> Modification<'a> {
     const DONE: &'static str = "+done";
 
-    pub fn new(str: &'a str, now: &Date, date_keys: &[Key]) -> Result<Self> {
+    pub fn new(
+        str: &'a str,
+        now: &Date,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<Self> {
         let full = str;
         let (str, negate) = match str.strip_prefix('-').or_else(|| str.strip_prefix('!')) {
             Some(str) => (str, true),
@@ -57,16 +71,28 @@ impl<'a> Modification<'a> {
                 true => Modification::RemoveContext(ctx),
                 false => Modification::AddContext(ctx),
             }
-        } else if let Some(pair) = ModPair::new(str, negate, now, date_keys)? {
+        } else if let Some(pair) = ModPair::new(str, negate, now, date_keys, calendar)? {
             Modification::SetPair(pair)
         } else if let Some(text) = full.strip_prefix(">>") {
-            Modification::Append(text.to_owned())
+            Modification::Append(Modification::stamp_annotation(text, now, calendar))
         } else {
-            Modification::SetBody(full.to_owned())
+            Modification::SetBody(Modification::stamp_annotation(full, now, calendar))
         })
     }
 
-    pub fn apply(&self, task: &mut TaskBuf, date_keys: &[Key]) -> ModOutput {
+    // Annotations written by Chore lead with the day they were written, e.g. `| 2001-02-03 ...`,
+    // which the `annotation.before:`, `.after:`, `.in:` and `.between:` filters go by.
+    // The `|` may be followed by the note in the same argument, e.g. `>>| left a message` or
+    // `>>|left`.
+    fn stamp_annotation(text: &str, now: &Date, calendar: &Calendar) -> String {
+        match (text.trim_start().strip_prefix('|'), Date::new("today", now, calendar)) {
+            (Some(""), Some(today)) => format!("| {}", today),
+            (Some(note), Some(today)) => format!("| {} {}", today, note.trim_start()),
+            _ => text.to_owned(),
+        }
+    }
+
+    pub fn apply(&self, task: &mut TaskBuf, date_keys: &[Key], calendar: &Calendar) -> ModOutput {
         let mut add = None;
         let mut remove_similar = false;
         match self {
@@ -82,7 +108,7 @@ impl<'a> Modification<'a> {
                         if let Some(entry) =
                             task.get_entry().and_then(|e| Date::from_abs(e.as_str()))
                         {
-                            if let Some(new_date) = Date::from_rel(off.as_str(), &entry) {
+                            if let Some(new_date) = Date::from_rel(off.as_str(), &entry, calendar) {
                                 new.set_entry(Some(Entry(&new_date.to_string())))
                             }
                         }
@@ -94,7 +120,7 @@ impl<'a> Modification<'a> {
                                 Some(value) => value,
                                 None => continue,
                             };
-                            if let Some(new_date) = Date::from_rel(off.as_str(), &value) {
+                            if let Some(new_date) = Date::from_rel(off.as_str(), &value, calendar) {
                                 new.set_value(key, &Value::new(&new_date.to_string()))
                             }
                         }
@@ -130,6 +156,37 @@ impl<'a> Modification<'a> {
                 ModPair::RemovePriority => task.set_priority(None),
                 ModPair::RemoveEntry => task.set_entry(None),
                 ModPair::RemoveKey(k) => task.remove_pair(k),
+                ModPair::SetDateFrom(target, base, offsets) => {
+                    let task_ref = task.as_task();
+                    let base = match base {
+                        DateField::End => {
+                            task_ref.get_end().and_then(|e| Date::from_abs(e.as_str()))
+                        }
+                        DateField::Entry => task_ref
+                            .get_entry()
+                            .and_then(|e| Date::from_abs(e.as_str())),
+                        DateField::Value(k) => task_ref
+                            .get_value(k)
+                            .and_then(|v| Date::from_abs(v.as_str())),
+                    };
+                    // Tasks lacking the base date are left as they are.
+                    if let Some(date) = base
+                        .and_then(|date| date.add_offsets(offsets, calendar))
+                        .map(|date| date.to_string())
+                    {
+                        // End and entry dates only hold whole days, so finer bases are skipped.
+                        match target {
+                            DateField::End if End::new(&date, Stage::End).is_some() => {
+                                task.set_end(Some(End(&date)))
+                            }
+                            DateField::Entry if Entry::new(&date, Stage::Entry).is_some() => {
+                                task.set_entry(Some(Entry(&date)))
+                            }
+                            DateField::End | DateField::Entry => {}
+                            DateField::Value(k) => task.set_value(k, &Value::new(&date)),
+                        }
+                    }
+                }
             },
             Modification::Append(v) => task.append_text(v),
             Modification::SetBody(v) => {
@@ -150,7 +207,13 @@ impl<'a> ModPair<'a> {
     const PRI: &'static str = "pri";
     const ENTRY: &'static str = "entry";
 
-    pub fn new(str: &'a str, negate: bool, now: &Date, date_keys: &[Key]) -> Result<Option<Self>> {
+    pub fn new(
+        str: &'a str,
+        negate: bool,
+        now: &Date,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<Option<Self>> {
         let (key, value) = match str.split_once(':') {
             None => return Ok(None),
             Some((key, value)) => (key, value),
@@ -158,10 +221,32 @@ impl<'a> ModPair<'a> {
 
         let is_date_key = date_keys.iter().any(|k| k == &Key::new(key));
         let empty_value = value.is_empty();
+        let date_from = match Date::new(value, now, calendar) {
+            Some(_) => None,
+            None => ModPair::date_from(value, date_keys),
+        };
 
         Ok(Some(match (key, negate, empty_value) {
+            (ModPair::END, false, false) if date_from.is_some() => {
+                let (base, offsets) = date_from.unwrap();
+                match offsets.contains(['s', 'm', 'h']) {
+                    true => return Err(InvalidEnd(str.to_owned())),
+                    false => ModPair::SetDateFrom(DateField::End, base, offsets),
+                }
+            }
+            (ModPair::ENTRY, false, false) if date_from.is_some() => {
+                let (base, offsets) = date_from.unwrap();
+                match offsets.contains(['s', 'm', 'h']) {
+                    true => return Err(InvalidEntry(str.to_owned())),
+                    false => ModPair::SetDateFrom(DateField::Entry, base, offsets),
+                }
+            }
+            (_, false, false) if is_date_key && date_from.is_some() => {
+                let (base, offsets) = date_from.unwrap();
+                ModPair::SetDateFrom(DateField::Value(Key::new(key)), base, offsets)
+            }
             (ModPair::END, false, false) => {
-                match Date::new(value, now).map(|date| date.to_string()) {
+                match Date::new(value, now, calendar).map(|date| date.to_string()) {
                     Some(date) => match End::new(&date, Stage::End) {
                         Some(_) => ModPair::SetEnd(date),
                         None => return Err(InvalidEnd(str.to_owned())),
@@ -180,7 +265,7 @@ impl<'a> ModPair<'a> {
                 }
             }
             (ModPair::ENTRY, false, false) => {
-                match Date::new(value, now).map(|date| date.to_string()) {
+                match Date::new(value, now, calendar).map(|date| date.to_string()) {
                     Some(date) => match Entry::new(&date, Stage::Entry) {
                         Some(_) => ModPair::SetEntry(date),
                         None => return Err(InvalidEntry(str.to_owned())),
@@ -188,7 +273,7 @@ impl<'a> ModPair<'a> {
                     None => return Err(KeyExpectsDateValue(str.to_string())),
                 }
             }
-            (_, false, false) if is_date_key => match Date::new(value, now) {
+            (_, false, false) if is_date_key => match Date::new(value, now, calendar) {
                 Some(date) => ModPair::SetValue(Key::new(key), date.to_string()),
                 None => return Err(KeyExpectsDateValue(str.to_string())),
             },
@@ -205,4 +290,17 @@ impl<'a> ModPair<'a> {
             (_, true, true) => ModPair::RemoveKey(Key::new(key)),
         }))
     }
+
+    // Parses values such as `due-3d` or `entry` which name another date on the task, optionally
+    // followed by offsets.
+    fn date_from(value: &'a str, date_keys: &[Key]) -> Option<(DateField<'a>, &'a str)> {
+        let (base, offsets) = Date::split_expression(value).unwrap_or((value, ""));
+        let base = match base {
+            ModPair::END => DateField::End,
+            ModPair::ENTRY => DateField::Entry,
+            _ if date_keys.iter().any(|k| k == &Key::new(base)) => DateField::Value(Key::new(base)),
+            _ => return None,
+        };
+        Some((base, offsets))
+    }
 }
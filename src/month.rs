@@ -0,0 +1,250 @@
+//
+// Months drawn as grids of days for the `calendar` command, marking the days on which tasks' date
+// key values fall.  A value lands on every day its span overlaps, so `due:2001-02-03T14` marks the
+// 3rd while `due:2001-W05` marks each day of that week.
+//
+
+use crate::calendar::Calendar;
+use crate::color::*;
+use crate::date::{Date, Duration};
+use crate::env::Env;
+use crate::error::*;
+use crate::field::*;
+use crate::print::*;
+use crate::task::Task;
+use crate::theme::{Paint, Part};
+
+// Arguments to `calendar`, each given as `name:value`.
+pub struct CalendarOptions<'a> {
+    months: usize,
+    keys: Vec<Key<'a>>,
+    from: Date,
+    tasks: bool,
+}
+
+// What the tasks on a day amount to, from least to most pressing.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum Mark {
+    None,
+    Completed,
+    Pending,
+    Overdue,
+}
+
+impl<'a> CalendarOptions<'a> {
+    const MONTHS: &'static str = "months";
+    const KEYS: &'static str = "keys";
+    const FROM: &'static str = "from";
+    const TASKS: &'static str = "tasks";
+
+    const SHOW: &'static str = "show";
+    const HIDE: &'static str = "hide";
+
+    pub fn new(
+        args: &[&'a str],
+        date_keys: &[Key<'a>],
+        now: &Date,
+        calendar: &Calendar,
+    ) -> Result<Self> {
+        let mut options = CalendarOptions {
+            months: 1,
+            keys: date_keys.iter().map(|key| Key::new(key.as_str())).collect(),
+            from: now.clone(),
+            tasks: false,
+        };
+
+        for arg in args {
+            let (name, value) = match arg.split_once(':') {
+                Some((name, value)) => (name, value),
+                None => return Err(InvalidCommandArg(arg.to_string())),
+            };
+            match name {
+                CalendarOptions::MONTHS => match value.parse::<usize>().ok().filter(|n| *n > 0) {
+                    Some(months) => options.months = months,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                CalendarOptions::KEYS => match Key::new_date_list(value, date_keys) {
+                    Some(keys) => options.keys = keys,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                CalendarOptions::FROM => match Date::new(value, now, calendar) {
+                    Some(from) => options.from = from,
+                    None => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                CalendarOptions::TASKS => match value {
+                    CalendarOptions::SHOW => options.tasks = true,
+                    CalendarOptions::HIDE => options.tasks = false,
+                    _ => return Err(InvalidCommandArg(arg.to_string())),
+                },
+                _ => return Err(InvalidCommandArg(arg.to_string())),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl Mark {
+    const OVERDUE: char = '!';
+    const PENDING: char = '*';
+    const COMPLETED: char = 'x';
+
+    fn new(task: &Task, date: &Date, now: &Date) -> Self {
+        match (task.is_completed(), date.before(now)) {
+            (true, _) => Mark::Completed,
+            (false, true) => Mark::Overdue,
+            (false, false) => Mark::Pending,
+        }
+    }
+
+    fn char(&self) -> char {
+        match self {
+            Mark::None => ' ',
+            Mark::Completed => Mark::COMPLETED,
+            Mark::Pending => Mark::PENDING,
+            Mark::Overdue => Mark::OVERDUE,
+        }
+    }
+
+    fn fg(&self, paint: Paint) -> Fg {
+        match self {
+            Mark::None => Fg::Default,
+            Mark::Completed => paint.fg(Part::Completed),
+            Mark::Pending => paint.fg(Part::Pending),
+            Mark::Overdue => paint.fg(Part::Overdue),
+        }
+    }
+}
+
+pub struct Month;
+
+impl Month {
+    const WEEKDAYS: [&'static str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+    // Each day is its number right-aligned in two columns followed by its mark.
+    const CELL_WIDTH: usize = 3;
+    // Tasks listed under the grid are indented this far beneath their day.
+    const TASK_INDENT: usize = 2;
+
+    // Prints each month from the one containing the start, separated by blank lines.
+    pub fn print_all(
+        stdout: &mut String,
+        options: &CalendarOptions,
+        tasks: &[(Number, Task)],
+        now: &Date,
+        env: &Env,
+    ) {
+        let today = now.first_day();
+        let mut month = options.from.to_month();
+        for i in 0..options.months {
+            if i > 0 {
+                stdout.push('\n');
+            }
+            Month::print(stdout, &month, options, tasks, now, &today, env);
+            month += Duration::Months(1);
+        }
+        Fg::Default.print(stdout, env.paint());
+    }
+
+    fn print(
+        stdout: &mut String,
+        month: &Date,
+        options: &CalendarOptions,
+        tasks: &[(Number, Task)],
+        now: &Date,
+        today: &Date,
+        env: &Env,
+    ) {
+        // Each day of the month with the indices of the tasks falling on it and their mark.
+        let mut days = Vec::new();
+        let mut day = month.first_day();
+        while day.within(month) {
+            let mut members = Vec::new();
+            let mut mark = Mark::None;
+            for (i, (_, task)) in tasks.iter().enumerate() {
+                for key in &options.keys {
+                    let date = match task.get_value(key).and_then(|v| Date::from_abs(v.as_str())) {
+                        Some(date) if date.overlaps(&day) => date,
+                        _ => continue,
+                    };
+                    let task_mark = Mark::new(task, &date, now);
+                    if task_mark > mark {
+                        mark = task_mark;
+                    }
+                    if !members.contains(&i) {
+                        members.push(i);
+                    }
+                }
+            }
+            days.push((day.clone(), members, mark));
+            day += Duration::Days(1);
+        }
+
+        Fg::Default.print(stdout, env.paint());
+        stdout.push_str(&month.to_string());
+        stdout.push('\n');
+        stdout.push_str(&Month::WEEKDAYS.join("  "));
+        stdout.push('\n');
+
+        let first_weekday = days.first().map_or(0, |(day, ..)| day.day_of_week());
+        for _ in 0..first_weekday {
+            stdout.push_str(&" ".repeat(Month::CELL_WIDTH + 1));
+        }
+        let paint = env.paint();
+        for (i, (day, _, mark)) in days.iter().enumerate() {
+            let digits = match day == today {
+                true => paint.fg(Part::Current),
+                false => mark.fg(paint),
+            };
+            digits.print(stdout, paint);
+            stdout.push_str(&format!("{:>2}", day.day_of_month()));
+
+            let ends_row = day.day_of_week() == 6 || i + 1 == days.len();
+            if *mark != Mark::None || !ends_row {
+                mark.fg(paint).print(stdout, paint);
+                stdout.push(mark.char());
+            }
+            match ends_row {
+                true => stdout.push('\n'),
+                false => stdout.push(' '),
+            }
+        }
+
+        if !options.tasks {
+            return;
+        }
+        let max_nr_digits = days
+            .iter()
+            .flat_map(|(_, members, _)| members.iter().map(|i| tasks[*i].0.digits()))
+            .max();
+        let max_nr_digits = match max_nr_digits {
+            Some(digits) => digits,
+            None => return,
+        };
+        stdout.push('\n');
+        for (day, members, _) in days.iter().filter(|(_, members, _)| !members.is_empty()) {
+            Fg::Default.print(stdout, env.paint());
+            stdout.push_str(&day.to_org());
+            stdout.push('\n');
+            for i in members {
+                Month::print_task(stdout, &tasks[*i], max_nr_digits, env);
+            }
+        }
+    }
+
+    // Prints a task indented beneath its heading, its number right-aligned to the widest.  Shared
+    // with the agenda.
+    pub fn print_task(
+        stdout: &mut String,
+        (nr, task): &(Number, Task),
+        max_nr_digits: usize,
+        env: &Env,
+    ) {
+        for _ in 0..(Month::TASK_INDENT + max_nr_digits - nr.digits()) {
+            stdout.push(' ');
+        }
+        nr.print(stdout, env.paint());
+        stdout.push(' ');
+        task.print(stdout, env.paint_task(task, *nr));
+        stdout.push('\n');
+    }
+}
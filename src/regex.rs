@@ -3,28 +3,92 @@
 
 use crate::error::*;
 
-pub struct Regex(regex::Regex);
+pub struct Regex {
+    regex: regex::Regex,
+    scope: Scope,
+}
+
+// Which part of a task a filter regex is matched against.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scope {
+    Line,
+    Body,
+    Annotations,
+}
 
 impl Regex {
+    const FLAG_CASE_INSENSITIVE: char = 'i';
+    const FLAG_LITERAL: char = 'l';
+    const FLAG_BODY: char = 'b';
+    const FLAG_ANNOTATIONS: char = 'a';
+
+    // Parses `/pattern/flags`, where flags may be any of `i` (case-insensitive), `l` (literal
+    // text rather than a pattern), `b` (body words only) and `a` (annotations only).  Flags are
+    // written at most once each and in that order, so that paths such as `/usr/lib` are not
+    // mistaken for a pattern followed by flags.
     pub fn new(str: &str) -> Result<Option<Self>> {
-        let pattern = match str.strip_prefix('/').and_then(|str| str.strip_suffix('/')) {
-            Some(pat) => pat,
+        let (pattern, flags) = match str
+            .strip_prefix('/')
+            .and_then(|str| str.rfind('/').map(|i| (&str[..i], &str[i + 1..])))
+        {
+            Some((pattern, flags)) => (pattern, flags),
             None => return Ok(None),
         };
-        match regex::Regex::new(pattern) {
-            Ok(regex) => Ok(Some(Regex(regex))),
+        let mut order = [
+            Regex::FLAG_CASE_INSENSITIVE,
+            Regex::FLAG_LITERAL,
+            Regex::FLAG_BODY,
+            Regex::FLAG_ANNOTATIONS,
+        ]
+        .iter();
+        if !flags.chars().all(|c| order.any(|flag| *flag == c)) {
+            return Ok(None);
+        }
+
+        let scope = match (
+            flags.contains(Regex::FLAG_BODY),
+            flags.contains(Regex::FLAG_ANNOTATIONS),
+        ) {
+            (false, false) => Scope::Line,
+            (true, false) => Scope::Body,
+            (false, true) => Scope::Annotations,
+            (true, true) => return Err(InvalidRegex(str.to_string())),
+        };
+        let pattern = match flags.contains(Regex::FLAG_LITERAL) {
+            true => regex::escape(pattern),
+            false => pattern.to_owned(),
+        };
+        let pattern = match flags.contains(Regex::FLAG_CASE_INSENSITIVE) {
+            true => format!("(?i){}", pattern),
+            false => pattern,
+        };
+
+        match regex::Regex::new(&pattern) {
+            Ok(regex) => Ok(Some(Regex { regex, scope })),
             Err(_) => Err(InvalidRegex(str.to_string())),
         }
     }
 
+    // A bare pattern, without surrounding slashes.
+    pub fn from_pattern(pattern: &str) -> Option<Self> {
+        regex::Regex::new(pattern).ok().map(|regex| Regex {
+            regex,
+            scope: Scope::Line,
+        })
+    }
+
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
     pub fn is_match(&self, str: &str) -> bool {
-        self.0.is_match(str)
+        self.regex.is_match(str)
     }
 }
 
 impl PartialEq for Regex {
     fn eq(&self, other: &Regex) -> bool {
-        self.0.as_str() == other.0.as_str()
+        self.regex.as_str() == other.regex.as_str() && self.scope == other.scope
     }
 }
 
@@ -41,6 +105,10 @@ mod tests {
             ("/^foo/", "/^bar/", false),
             ("/foo/", "/bar/", false),
             ("/bar$/", "/^bar/", false),
+            ("/foo/i", "/foo/i", true),
+            ("/foo/i", "/foo/", false),
+            ("/foo/b", "/foo/a", false),
+            ("/f.o/l", "/f\\.o/", true),
         ] {
             assert_eq!(
                 Regex::new(left).unwrap() == Regex::new(right).unwrap(),
@@ -49,6 +117,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn flags() {
+        for (regex, expect) in &[
+            ("/foo/", Some(Scope::Line)),
+            ("/foo/b", Some(Scope::Body)),
+            ("/foo/ia", Some(Scope::Annotations)),
+            // not a regex filter
+            ("/foo", None),
+            ("/usr/bin", None),
+            ("/usr/lib", None),
+            ("/foo/x", None),
+            ("/foo/ii", None),
+            ("/foo/ai", None),
+        ] {
+            assert_eq!(Regex::new(regex).unwrap().map(|r| r.scope()), *expect);
+        }
+        assert!(Regex::new("/foo/ba").is_err());
+        assert!(Regex::new("/(foo/").is_err());
+    }
+
     #[test]
     fn is_match() {
         for (regex, text, expect) in &[
@@ -58,6 +146,13 @@ mod tests {
             ("/^foo/", "barfoo", false),
             ("/foo/", "bar", false),
             ("/bar$/", "barfoo", false),
+            ("/FOO/i", "foobar", true),
+            ("/FOO/", "foobar", false),
+            ("/a.c/", "abc", true),
+            ("/a.c/l", "abc", false),
+            ("/a.c/l", "xa.cx", true),
+            ("/(a/il", "(A", true),
+            ("/a/b/", "a/b", true),
         ] {
             assert_eq!(Regex::new(regex).unwrap().unwrap().is_match(text), *expect);
         }
@@ -0,0 +1,149 @@
+//
+// Orderings for listings, given as comma-separated keys each optionally followed by `+` for
+// ascending or `-` for descending, e.g. `due+,urgency-`.  Tasks lacking a key's value sort after
+// those having one, whichever the direction.
+//
+
+use crate::date::Date;
+use crate::env::Env;
+use crate::field::*;
+use crate::task::Task;
+use crate::token::Token;
+use std::cmp::Ordering;
+
+pub struct SortKey {
+    field: SortField,
+    descending: bool,
+}
+
+enum SortField {
+    Priority,
+    Entry,
+    End,
+    Project,
+    Urgency,
+    Date(String),
+    Value(String),
+}
+
+impl SortKey {
+    const PRI: &'static str = "pri";
+    const ENTRY: &'static str = "entry";
+    const END: &'static str = "end";
+    const PROJECT: &'static str = "project";
+    const URGENCY: &'static str = "urgency";
+
+    const SEPARATOR: char = ',';
+    const ASCENDING: char = '+';
+    const DESCENDING: char = '-';
+
+    pub fn new_list(str: &str, date_keys: &[Key]) -> Option<Vec<SortKey>> {
+        str.split(SortKey::SEPARATOR)
+            .map(|key| SortKey::new(key, date_keys))
+            .collect()
+    }
+
+    fn new(str: &str, date_keys: &[Key]) -> Option<SortKey> {
+        let (name, descending) = match str.strip_suffix(SortKey::DESCENDING) {
+            Some(name) => (name, true),
+            None => (str.strip_suffix(SortKey::ASCENDING).unwrap_or(str), false),
+        };
+        let field = match name {
+            "" => return None,
+            SortKey::PRI => SortField::Priority,
+            SortKey::ENTRY => SortField::Entry,
+            SortKey::END => SortField::End,
+            SortKey::PROJECT => SortField::Project,
+            SortKey::URGENCY => SortField::Urgency,
+            _ if date_keys.contains(&Key::new(name)) => SortField::Date(name.to_owned()),
+            _ => SortField::Value(name.to_owned()),
+        };
+        Some(SortKey { field, descending })
+    }
+
+    // Compares by each key in turn, falling through to the next on a tie.
+    pub fn compare(keys: &[SortKey], a: &Task, b: &Task, env: &Env) -> Ordering {
+        keys.iter()
+            .map(|key| key.compare_one(a, b, env))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+
+    fn compare_one(&self, a: &Task, b: &Task, env: &Env) -> Ordering {
+        match &self.field {
+            SortField::Priority => {
+                self.compare_present(a.get_priority(), b.get_priority(), |a, b| a.cmp(b))
+            }
+            SortField::Entry => self.compare_present(
+                a.get_entry().and_then(|e| Date::from_abs(e.as_str())),
+                b.get_entry().and_then(|e| Date::from_abs(e.as_str())),
+                |a, b| a.compare(b),
+            ),
+            SortField::End => self.compare_present(
+                a.get_end().and_then(|e| Date::from_abs(e.as_str())),
+                b.get_end().and_then(|e| Date::from_abs(e.as_str())),
+                |a, b| a.compare(b),
+            ),
+            SortField::Project => {
+                self.compare_present(SortKey::project(a), SortKey::project(b), |a, b| a.cmp(b))
+            }
+            SortField::Urgency => self.directed(
+                env.urgency(a)
+                    .partial_cmp(&env.urgency(b))
+                    .unwrap_or(Ordering::Equal),
+            ),
+            SortField::Date(key) => {
+                let date = |task: &Task| {
+                    task.get_value(&Key::new(key))
+                        .and_then(|v| Date::from_abs(v.as_str()))
+                };
+                self.compare_present(date(a), date(b), |a, b| a.compare(b))
+            }
+            SortField::Value(key) => {
+                let value = |task: &Task| {
+                    task.get_value(&Key::new(key))
+                        .map(|v| v.as_str().to_owned())
+                };
+                self.compare_present(value(a), value(b), |a, b| SortKey::compare_values(a, b))
+            }
+        }
+    }
+
+    fn directed(&self, ordering: Ordering) -> Ordering {
+        match self.descending {
+            true => ordering.reverse(),
+            false => ordering,
+        }
+    }
+
+    // Only present values follow the sort direction; missing ones always end up last.
+    fn compare_present<T, F>(&self, a: Option<T>, b: Option<T>, compare: F) -> Ordering
+    where
+        F: Fn(&T, &T) -> Ordering,
+    {
+        match (a, b) {
+            (Some(a), Some(b)) => self.directed(compare(&a, &b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    // Numbers compare numerically and before any text, which compares as strings.
+    pub fn compare_values(a: &str, b: &str) -> Ordering {
+        let number = |str: &str| str.parse::<f64>().ok().filter(|n| n.is_finite());
+        match (number(a), number(b)) {
+            (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.cmp(b),
+        }
+    }
+
+    fn project(task: &Task) -> Option<String> {
+        task.iter().find_map(|(token, _)| match token {
+            Token::Project(p) => Some(p.as_str().to_owned()),
+            _ => None,
+        })
+    }
+}
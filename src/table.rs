@@ -0,0 +1,277 @@
+//
+// Tasks rendered as aligned columns rather than as raw task lines.  Widths are measured in
+// terminal cells, so wide characters such as CJK take two and combining marks none.  When a
+// terminal width is known, the body column gives way to fit it, either wrapping onto further lines
+// or being cut short.
+//
+
+use crate::color::Fg;
+use crate::column::Column;
+use crate::date::Date;
+use crate::field::Number;
+use crate::print::*;
+use crate::task::Task;
+use crate::theme::{Paint, Part};
+
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    widths: Vec<usize>,
+    parts: Vec<Part>,
+    right_aligned: Vec<bool>,
+    // The column which gives way to fit the terminal, if any
+    flexible: Option<usize>,
+    overflow: Overflow,
+}
+
+#[derive(Clone, Copy)]
+pub enum Overflow {
+    Wrap,
+    Truncate,
+}
+
+impl Overflow {
+    const WRAP: &'static str = "wrap";
+    const TRUNCATE: &'static str = "truncate";
+
+    pub fn new(str: &str) -> Option<Self> {
+        match str {
+            Overflow::WRAP => Some(Overflow::Wrap),
+            Overflow::TRUNCATE => Some(Overflow::Truncate),
+            _ => None,
+        }
+    }
+}
+
+impl Table {
+    const SEPARATOR: &'static str = "  ";
+    const ELLIPSIS: char = '…';
+
+    // The body column is never squeezed narrower than this.
+    const MIN_FLEXIBLE_WIDTH: usize = 10;
+
+    // `width` is that of the terminal less any indentation, if known.
+    pub fn new(
+        columns: &[Column],
+        tasks: &[(Number, Task)],
+        now: &Date,
+        width: Option<usize>,
+        overflow: Overflow,
+    ) -> Table {
+        let headers = columns
+            .iter()
+            .map(|column| column.name().to_owned())
+            .collect::<Vec<_>>();
+        let rows = tasks
+            .iter()
+            .map(|(nr, task)| {
+                columns
+                    .iter()
+                    .map(|column| column.render(task, *nr, now))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut widths = headers.iter().map(|h| text_width(h)).collect::<Vec<_>>();
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(text_width(cell));
+            }
+        }
+
+        let flexible = columns
+            .iter()
+            .position(Column::is_body)
+            .or_else(|| columns.len().checked_sub(1));
+        if let (Some(i), Some(width)) = (flexible, width) {
+            let total = widths.iter().sum::<usize>()
+                + Table::SEPARATOR.len() * widths.len().saturating_sub(1);
+            if total > width {
+                let rest = total - widths[i];
+                let least = Table::MIN_FLEXIBLE_WIDTH.min(widths[i]);
+                widths[i] = width.saturating_sub(rest).max(least);
+            }
+        }
+
+        Table {
+            headers,
+            rows,
+            widths,
+            parts: columns.iter().map(Column::part).collect(),
+            right_aligned: columns.iter().map(Column::is_right_aligned).collect(),
+            flexible,
+            overflow,
+        }
+    }
+
+    pub fn print_header(&self, stdout: &mut String, indent: usize, paint: Paint) {
+        let cells = self
+            .headers
+            .iter()
+            .map(|header| vec![header.clone()])
+            .collect::<Vec<_>>();
+        let fgs = vec![Fg::Default; self.headers.len()];
+        self.print_lines(stdout, &cells, &fgs, indent, paint);
+    }
+
+    // Prints the row of the `i`th task given to `new`, painted as that task.
+    pub fn print_row(&self, stdout: &mut String, i: usize, indent: usize, paint: Paint) {
+        let cells = self.rows[i]
+            .iter()
+            .enumerate()
+            .map(
+                |(c, cell)| match (Some(c) == self.flexible, self.overflow) {
+                    (true, Overflow::Wrap) => wrap(cell, self.widths[c]),
+                    _ => vec![truncate(cell, self.widths[c])],
+                },
+            )
+            .collect::<Vec<_>>();
+        let fgs = self
+            .parts
+            .iter()
+            .map(|part| paint.fg(*part))
+            .collect::<Vec<_>>();
+        self.print_lines(stdout, &cells, &fgs, indent, paint);
+    }
+
+    // Prints cells side by side, each possibly spanning several lines.
+    fn print_lines(
+        &self,
+        stdout: &mut String,
+        cells: &[Vec<String>],
+        fgs: &[Fg],
+        indent: usize,
+        paint: Paint,
+    ) {
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+        for l in 0..height {
+            let texts = cells
+                .iter()
+                .map(|lines| lines.get(l).map(String::as_str).unwrap_or(""))
+                .collect::<Vec<_>>();
+            // Trailing empty cells are left off rather than padded.
+            let last = match texts.iter().rposition(|text| !text.is_empty()) {
+                Some(last) => last,
+                None => {
+                    stdout.push('\n');
+                    continue;
+                }
+            };
+            push_spaces(stdout, indent);
+            for (c, text) in texts.iter().enumerate().take(last + 1) {
+                if c > 0 {
+                    stdout.push_str(Table::SEPARATOR);
+                }
+                let padding = self.widths[c].saturating_sub(text_width(text));
+                if self.right_aligned[c] {
+                    push_spaces(stdout, padding);
+                }
+                fgs[c].print(stdout, paint);
+                stdout.push_str(text);
+                if !self.right_aligned[c] && c != last {
+                    push_spaces(stdout, padding);
+                }
+            }
+            Fg::Default.print(stdout, paint);
+            stdout.push('\n');
+        }
+    }
+}
+
+fn push_spaces(stdout: &mut String, count: usize) {
+    for _ in 0..count {
+        stdout.push(' ');
+    }
+}
+
+// Terminal cells taken by a character: none for control characters and combining marks, two for
+// wide East Asian characters and emoji, and one otherwise.
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x00..=0x1F | 0x7F..=0x9F => 0,
+        0x0300..=0x036F
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD
+        | 0x0610..=0x061A
+        | 0x064B..=0x065F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x2FFFD
+        | 0x30000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+pub fn text_width(str: &str) -> usize {
+    str.chars().map(char_width).sum()
+}
+
+// Cuts text to fit the width, marking the cut with an ellipsis.
+fn truncate(str: &str, width: usize) -> String {
+    if text_width(str) <= width {
+        return str.to_owned();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in str.chars() {
+        if used + char_width(c) + 1 > width {
+            break;
+        }
+        used += char_width(c);
+        out.push(c);
+    }
+    if width > 0 {
+        out.push(Table::ELLIPSIS);
+    }
+    out
+}
+
+// Breaks text into lines fitting the width at spaces, splitting words too long for a line.
+fn wrap(str: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in str.split(' ').filter(|word| !word.is_empty()) {
+        let needed = match line.is_empty() {
+            true => text_width(word),
+            false => text_width(&line) + 1 + text_width(word),
+        };
+        if needed <= width {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            if text_width(&line) + char_width(c) > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
@@ -0,0 +1,159 @@
+//
+// Tags which are not written on a task but computed from it, such as `+overdue`.  The dates they
+// compare against, such as the end of today, are worked out from now before any task is read.
+//
+
+use crate::calendar::Calendar;
+use crate::date::Date;
+use crate::depends::Dependencies;
+use crate::error::*;
+use crate::field::Key;
+use crate::task::Task;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VirtualTag {
+    Overdue,
+    Today,
+    Tomorrow,
+    Week,
+    Soon,
+    Waiting,
+    Annotated,
+    Recurring,
+    Blocked,
+    Ready,
+}
+
+// What the date based tags compare against.  Without `due` or `wait` listed as date keys, the tags
+// using them never apply.
+pub struct TagDates {
+    now: Date,
+    today: Date,
+    tomorrow: Date,
+    week: Date,
+    soon: Date,
+    due: bool,
+    wait: bool,
+}
+
+impl VirtualTag {
+    const OVERDUE: &'static str = "+overdue";
+    const TODAY: &'static str = "+today";
+    const TOMORROW: &'static str = "+tomorrow";
+    const WEEK: &'static str = "+week";
+    const SOON: &'static str = "+soon";
+    const WAITING: &'static str = "+waiting";
+    const ANNOTATED: &'static str = "+annotated";
+    const RECURRING: &'static str = "+recurring";
+    const BLOCKED: &'static str = "+blocked";
+    const READY: &'static str = "+ready";
+
+    const KEY_DUE: &'static str = "due";
+    const KEY_WAIT: &'static str = "wait";
+    const KEY_RECUR: &'static str = "recur";
+
+    pub const ALL: [VirtualTag; 10] = [
+        VirtualTag::Overdue,
+        VirtualTag::Today,
+        VirtualTag::Tomorrow,
+        VirtualTag::Week,
+        VirtualTag::Soon,
+        VirtualTag::Waiting,
+        VirtualTag::Annotated,
+        VirtualTag::Recurring,
+        VirtualTag::Blocked,
+        VirtualTag::Ready,
+    ];
+
+    pub fn new(str: &str) -> Option<Self> {
+        VirtualTag::ALL
+            .iter()
+            .copied()
+            .find(|tag| tag.as_str() == str)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VirtualTag::Overdue => VirtualTag::OVERDUE,
+            VirtualTag::Today => VirtualTag::TODAY,
+            VirtualTag::Tomorrow => VirtualTag::TOMORROW,
+            VirtualTag::Week => VirtualTag::WEEK,
+            VirtualTag::Soon => VirtualTag::SOON,
+            VirtualTag::Waiting => VirtualTag::WAITING,
+            VirtualTag::Annotated => VirtualTag::ANNOTATED,
+            VirtualTag::Recurring => VirtualTag::RECURRING,
+            VirtualTag::Blocked => VirtualTag::BLOCKED,
+            VirtualTag::Ready => VirtualTag::READY,
+        }
+    }
+
+    // Whether a filter on one tag overrides a default filter on the other.  Being blocked and being
+    // ready are two sides of the same dependencies, so either overrides both.
+    pub fn conflicts(&self, other: &VirtualTag) -> bool {
+        let is_dependency =
+            |tag: &VirtualTag| matches!(tag, VirtualTag::Blocked | VirtualTag::Ready);
+        self == other || (is_dependency(self) && is_dependency(other))
+    }
+
+    // Date based tags only apply to pending tasks.
+    pub fn is_set(&self, task: &Task, dates: &TagDates, dependencies: &Dependencies) -> bool {
+        let due = || match dates.due && !task.is_completed() {
+            true => task
+                .get_value(&Key::new(VirtualTag::KEY_DUE))
+                .and_then(|v| Date::from_abs(v.as_str())),
+            false => None,
+        };
+
+        match self {
+            VirtualTag::Overdue => due().filter(|d| d.before(&dates.now)).is_some(),
+            VirtualTag::Today => due().filter(|d| d.within(&dates.today)).is_some(),
+            VirtualTag::Tomorrow => due().filter(|d| d.within(&dates.tomorrow)).is_some(),
+            VirtualTag::Week => due().filter(|d| d.within(&dates.week)).is_some(),
+            VirtualTag::Soon => due()
+                .filter(|d| !d.before(&dates.now) && !d.after(&dates.soon))
+                .is_some(),
+            VirtualTag::Waiting => match dates.wait && !task.is_completed() {
+                true => task
+                    .get_value(&Key::new(VirtualTag::KEY_WAIT))
+                    .and_then(|v| Date::from_abs(v.as_str()))
+                    .filter(|d| d.after(&dates.now))
+                    .is_some(),
+                false => false,
+            },
+            VirtualTag::Annotated => !task.get_annotations().is_empty(),
+            VirtualTag::Recurring => task.get_value(&Key::new(VirtualTag::KEY_RECUR)).is_some(),
+            VirtualTag::Blocked => dependencies.is_blocked(task),
+            VirtualTag::Ready => dependencies.is_ready(task),
+        }
+    }
+}
+
+impl TagDates {
+    const DEFAULT_SOON: &'static str = "7d";
+
+    // `soon` is how far ahead of now tasks count as due soon, e.g. `3d`.
+    pub fn new(
+        now: &Date,
+        soon: Option<&str>,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<TagDates> {
+        let soon = soon
+            .map(str::trim)
+            .filter(|str| !str.is_empty())
+            .unwrap_or(TagDates::DEFAULT_SOON);
+        let relative = |str| Date::from_rel(str, now, calendar);
+        Ok(TagDates {
+            now: now.clone(),
+            today: relative("today").unwrap(),
+            tomorrow: relative("tomorrow").unwrap(),
+            week: relative("thisweek").unwrap(),
+            soon: match relative(soon).filter(|date| !date.before(now)) {
+                Some(date) => date,
+                None => return Err(InvalidDueSoon(soon.to_owned())),
+            },
+            due: date_keys.contains(&Key::new(VirtualTag::KEY_DUE)),
+            wait: date_keys.contains(&Key::new(VirtualTag::KEY_WAIT)),
+        })
+    }
+}
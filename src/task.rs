@@ -1,6 +1,8 @@
+use crate::field::Context;
 use crate::field::End;
 use crate::field::Entry;
 use crate::field::Priority;
+use crate::field::Project;
 use crate::field::{Key, Pair, Value};
 use crate::taskiter::TaskIter;
 use crate::token::Token;
@@ -17,8 +19,8 @@ impl<'a> Task<'a> {
         Task(str)
     }
 
-    pub fn iter(&self) -> TaskIter {
-        TaskIter::new(&self.0)
+    pub fn iter(&self) -> TaskIter<'_> {
+        TaskIter::new(self.0)
     }
 
     pub fn into_iter(self) -> TaskIter<'a> {
@@ -26,7 +28,7 @@ impl<'a> Task<'a> {
     }
 
     pub fn as_str(&self) -> &str {
-        &self.0
+        self.0
     }
 
     pub fn into_str(self) -> &'a str {
@@ -37,7 +39,7 @@ impl<'a> Task<'a> {
         self.find_marker().is_some()
     }
 
-    pub fn get_end(&self) -> Option<End> {
+    pub fn get_end(&self) -> Option<End<'_>> {
         match self.find_end() {
             Some((end, _)) => Some(end),
             _ => None,
@@ -51,18 +53,58 @@ impl<'a> Task<'a> {
         }
     }
 
-    pub fn get_entry(&self) -> Option<Entry> {
+    pub fn get_entry(&self) -> Option<Entry<'_>> {
         match self.find_entry() {
             Some((entry, _)) => Some(entry),
             _ => None,
         }
     }
 
+    // Normal words preceding any annotation, i.e. excluding dates, tags and annotations.
+    pub fn get_body(&self) -> String {
+        self.iter()
+            .take_while(|(token, _)| !matches!(token, Token::Annotation(_)))
+            .filter(|(token, _)| matches!(token, Token::Normal(_)))
+            .map(|(_, range)| &self.0[range])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // Text of each annotation, with whitespace between words normalized to a single space.
+    pub fn get_annotations(&self) -> Vec<String> {
+        let mut notes: Vec<Vec<&str>> = Vec::new();
+        for (token, range) in self.iter() {
+            match (token, notes.last_mut()) {
+                (Token::Annotation(_), _) => notes.push(Vec::new()),
+                (Token::Space(_), _) => {}
+                (_, Some(note)) => note.push(&self.0[range]),
+                (_, None) => {}
+            }
+        }
+        notes.iter().map(|note| note.join(" ")).collect()
+    }
+
     pub fn has_token(&self, token: &Token) -> bool {
         self.iter().any(|(t, _)| &t == token)
     }
 
-    pub fn get_value(&self, key: &Key) -> Option<Value> {
+    // Whether the task has the project or one nested beneath it.
+    pub fn has_project(&self, proj: &Project) -> bool {
+        self.iter().any(|(token, _)| match token {
+            Token::Project(p) => proj.contains(&p),
+            _ => false,
+        })
+    }
+
+    // Whether the task has the context or one nested beneath it.
+    pub fn has_context(&self, ctx: &Context) -> bool {
+        self.iter().any(|(token, _)| match token {
+            Token::Context(c) => ctx.contains(&c),
+            _ => false,
+        })
+    }
+
+    pub fn get_value(&self, key: &Key) -> Option<Value<'_>> {
         match self.find_pair(key) {
             Some((pair, _)) => Some(pair.value),
             _ => None,
@@ -76,7 +118,7 @@ impl<'a> Task<'a> {
         })
     }
 
-    fn find_end(&self) -> Option<(End, Range<usize>)> {
+    fn find_end(&self) -> Option<(End<'_>, Range<usize>)> {
         self.iter().take(3).find_map(|(token, range)| match token {
             Token::End(end) => Some((end, range)),
             _ => None,
@@ -90,14 +132,14 @@ impl<'a> Task<'a> {
         })
     }
 
-    fn find_entry(&self) -> Option<(Entry, Range<usize>)> {
+    fn find_entry(&self) -> Option<(Entry<'_>, Range<usize>)> {
         self.iter().take(7).find_map(|(token, range)| match token {
             Token::Entry(entry) => Some((entry, range)),
             _ => None,
         })
     }
 
-    fn find_pair(&self, key: &Key) -> Option<(Pair, Range<usize>)> {
+    fn find_pair(&self, key: &Key) -> Option<(Pair<'_>, Range<usize>)> {
         for (token, range) in self.iter() {
             if let Token::Pair(pair) = token {
                 if pair.key == *key {
@@ -118,7 +160,7 @@ impl<'a> Task<'a> {
                 | Token::Space(_) => None,
                 _ => Some(range.start),
             })
-            .unwrap_or_else(|| self.0.len())
+            .unwrap_or(self.0.len())
     }
 }
 
@@ -127,7 +169,7 @@ impl TaskBuf {
         TaskBuf(str)
     }
 
-    pub fn as_task(&self) -> Task {
+    pub fn as_task(&self) -> Task<'_> {
         Task::new(&self.0)
     }
 
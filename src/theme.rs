@@ -0,0 +1,247 @@
+//
+// Colours for each kind of token and each priority, and rules colouring whole tasks which match
+// filters, read from `~/.chore/theme`.
+//
+
+use crate::calendar::Calendar;
+use crate::color::*;
+use crate::date::Date;
+use crate::env::Env;
+use crate::error::*;
+use crate::field::*;
+use crate::filter::Filter;
+use crate::task::Task;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Part {
+    Annotation,
+    Completed,
+    Context,
+    Current,
+    End,
+    Entry,
+    Key,
+    Marker,
+    Normal,
+    Number,
+    Overdue,
+    Past,
+    Pending,
+    Project,
+    Separator,
+    Upcoming,
+    Value,
+}
+
+#[derive(Default)]
+pub struct Palette {
+    parts: Vec<(Part, Fg)>,
+    // Colours of priorities set by the theme, as the letter and the colours behind and on it
+    priorities: Vec<(u8, Option<Fg>, Bg)>,
+}
+
+pub struct Theme<'a> {
+    pub palette: Palette,
+    pub rules: Vec<Rule<'a>>,
+}
+
+// Colours tasks matching all of the filters.
+pub struct Rule<'a> {
+    filters: Vec<Filter<'a>>,
+    fg: Fg,
+}
+
+// How to colour what is printed: whether to at all, in how many colours, from which palette and,
+// while printing a task matching a rule, in the rule's colour.
+#[derive(Clone, Copy)]
+pub struct Paint<'p> {
+    pub print_color: bool,
+    pub depth: Depth,
+    palette: &'p Palette,
+    rule: Option<Fg>,
+}
+
+impl Part {
+    const ANNOTATION: &'static str = "annotation";
+    const COMPLETED: &'static str = "completed";
+    const CONTEXT: &'static str = "context";
+    const CURRENT: &'static str = "current";
+    const END: &'static str = "end";
+    const ENTRY: &'static str = "entry";
+    const KEY: &'static str = "key";
+    const MARKER: &'static str = "marker";
+    const NORMAL: &'static str = "normal";
+    const NUMBER: &'static str = "number";
+    const OVERDUE: &'static str = "overdue";
+    const PAST: &'static str = "past";
+    const PENDING: &'static str = "pending";
+    const PROJECT: &'static str = "project";
+    const SEPARATOR: &'static str = "separator";
+    const UPCOMING: &'static str = "upcoming";
+    const VALUE: &'static str = "value";
+
+    fn new(str: &str) -> Option<Self> {
+        match str {
+            Part::ANNOTATION => Some(Part::Annotation),
+            Part::COMPLETED => Some(Part::Completed),
+            Part::CONTEXT => Some(Part::Context),
+            Part::CURRENT => Some(Part::Current),
+            Part::END => Some(Part::End),
+            Part::ENTRY => Some(Part::Entry),
+            Part::KEY => Some(Part::Key),
+            Part::MARKER => Some(Part::Marker),
+            Part::NORMAL => Some(Part::Normal),
+            Part::NUMBER => Some(Part::Number),
+            Part::OVERDUE => Some(Part::Overdue),
+            Part::PAST => Some(Part::Past),
+            Part::PENDING => Some(Part::Pending),
+            Part::PROJECT => Some(Part::Project),
+            Part::SEPARATOR => Some(Part::Separator),
+            Part::UPCOMING => Some(Part::Upcoming),
+            Part::VALUE => Some(Part::Value),
+            _ => None,
+        }
+    }
+}
+
+impl Palette {
+    fn fg(&self, part: Part) -> Fg {
+        let set = self
+            .parts
+            .iter()
+            .rev()
+            .find(|(p, _)| *p == part)
+            .map(|(_, fg)| *fg);
+        set.unwrap_or(match part {
+            Part::Annotation => Fg::Yellow,
+            Part::Context => Fg::Blue,
+            Part::Current => Fg::Yellow,
+            Part::End
+            | Part::Entry
+            | Part::Marker
+            | Part::Number
+            | Part::Past
+            | Part::Separator => Fg::LightGray,
+            Part::Completed | Part::Key => Fg::Green,
+            Part::Normal => Fg::White,
+            Part::Overdue => Fg::Red,
+            Part::Project => Fg::Magenta,
+            Part::Pending | Part::Upcoming | Part::Value => Fg::Cyan,
+        })
+    }
+}
+
+impl<'a> Rule<'a> {
+    // The colour of the first rule matching the task.
+    pub fn find(rules: &[Rule], task: &Task, nr: Number, env: &Env) -> Option<Fg> {
+        rules
+            .iter()
+            .find(|rule| rule.filters.iter().all(|f| f.keep(task, nr, env)))
+            .map(|rule| rule.fg)
+    }
+}
+
+impl<'p> Paint<'p> {
+    pub fn new(print_color: bool, depth: Depth, palette: &'p Palette) -> Self {
+        Paint {
+            print_color,
+            depth,
+            palette,
+            rule: None,
+        }
+    }
+
+    // Prints in the rule's colour, if any, in place of the colours of each part.
+    pub fn with_rule(self, rule: Option<Fg>) -> Self {
+        Paint { rule, ..self }
+    }
+
+    // The colour to print this part in: that of a rule matching the task, else the theme's.
+    pub fn fg(&self, part: Part) -> Fg {
+        match self.rule {
+            Some(fg) => fg,
+            None => self.palette.fg(part),
+        }
+    }
+
+    // The theme's colours for a priority letter, if it sets any.
+    pub fn priority(&self, letter: u8) -> Option<(Option<Fg>, Bg)> {
+        self.palette
+            .priorities
+            .iter()
+            .rev()
+            .find(|(l, ..)| *l == letter)
+            .map(|(_, fg, bg)| (*fg, *bg))
+    }
+}
+
+impl<'a> Theme<'a> {
+    const COMMENT: char = '#';
+    const RULE: &'static str = "rule";
+    const RULE_SEPARATOR: &'static str = "=";
+    const ON: &'static str = "on";
+
+    // Each line sets the colour of a part, e.g. `project 92`, of a priority, e.g. `(A) white on
+    // red`, or adds a rule, e.g. `rule +overdue = red`.  Later lines take precedence over earlier
+    // ones, except that the first matching rule wins.
+    pub fn new(
+        theme: Option<&'a str>,
+        now: &Date,
+        date_keys: &[Key],
+        calendar: &Calendar,
+    ) -> Result<Theme<'a>> {
+        let mut palette = Palette::default();
+        let mut rules = Vec::new();
+        for line in theme.unwrap_or("").lines() {
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let invalid = || InvalidTheme(line.trim().to_owned());
+            match words[..] {
+                [] => {}
+                [first, ..] if first.starts_with(Theme::COMMENT) => {}
+                [Theme::RULE, ref rest @ ..] => {
+                    let (filters, fg) = match rest {
+                        [filters @ .., Theme::RULE_SEPARATOR, fg] if !filters.is_empty() => {
+                            (filters, fg)
+                        }
+                        _ => return Err(invalid()),
+                    };
+                    let fg = Fg::new(fg).ok_or_else(invalid)?;
+                    let mut parsed = Vec::new();
+                    for filter in filters {
+                        match Filter::new(filter, now, date_keys, calendar)? {
+                            Some(filter) => parsed.push(filter),
+                            None => return Err(invalid()),
+                        }
+                    }
+                    rules.push(Rule {
+                        filters: parsed,
+                        fg,
+                    });
+                }
+                [name, ref colours @ ..] => {
+                    let priority = Priority::new(name, Stage::Priority).map(|pri| pri.as_u8());
+                    match (priority, Part::new(name), colours) {
+                        (Some(letter), _, [bg]) => {
+                            palette.priorities.push((
+                                letter,
+                                None,
+                                Bg::new(bg).ok_or_else(invalid)?,
+                            ));
+                        }
+                        (Some(letter), _, [fg, Theme::ON, bg]) => palette.priorities.push((
+                            letter,
+                            Some(Fg::new(fg).ok_or_else(invalid)?),
+                            Bg::new(bg).ok_or_else(invalid)?,
+                        )),
+                        (None, Some(part), [fg]) => {
+                            palette.parts.push((part, Fg::new(fg).ok_or_else(invalid)?))
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+            }
+        }
+
+        Ok(Theme { palette, rules })
+    }
+}
@@ -0,0 +1,159 @@
+//
+// A single number ranking how pressing a pending task is, summed from weighted terms in the manner
+// of Taskwarrior's urgency.  The weights may be overridden in `~/.chore/urgency`, one `name value`
+// pair per line.
+//
+
+use crate::date::Date;
+use crate::depends::Dependencies;
+use crate::error::*;
+use crate::field::*;
+use crate::tags::{TagDates, VirtualTag};
+use crate::task::Task;
+use crate::token::Token;
+
+pub struct Urgency {
+    now: Date,
+    priority: f64,
+    age: f64,
+    annotations: f64,
+    project: f64,
+    blocked: f64,
+    blocking: f64,
+    // Coefficients for how close the values of these date keys are, e.g. `due`
+    dates: Vec<(String, f64)>,
+    // Coefficients for having these projects, contexts or virtual tags
+    tags: Vec<(String, f64)>,
+}
+
+impl Urgency {
+    const PRIORITY: &'static str = "priority";
+    const AGE: &'static str = "age";
+    const ANNOTATIONS: &'static str = "annotations";
+    const PROJECT: &'static str = "project";
+    const BLOCKED: &'static str = "blocked";
+    const BLOCKING: &'static str = "blocking";
+    const COMMENT: char = '#';
+
+    // Tasks reach the full age coefficient after this many days.
+    const MAX_AGE: f64 = 365.0;
+
+    pub fn new(now: &Date, weights: Option<&str>) -> Result<Urgency> {
+        let mut urgency = Urgency {
+            now: now.clone(),
+            priority: 6.0,
+            age: 2.0,
+            annotations: 1.0,
+            project: 1.0,
+            blocked: -5.0,
+            blocking: 8.0,
+            dates: vec![("due".to_owned(), 12.0)],
+            tags: Vec::new(),
+        };
+
+        for line in weights.unwrap_or("").lines() {
+            let line = line.split(Urgency::COMMENT).next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, weight) = match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [name, weight] => match weight.parse::<f64>().ok().filter(|w| w.is_finite()) {
+                    Some(weight) => (name, weight),
+                    None => return Err(InvalidUrgencyWeight(line.to_owned())),
+                },
+                _ => return Err(InvalidUrgencyWeight(line.to_owned())),
+            };
+            match name {
+                Urgency::PRIORITY => urgency.priority = weight,
+                Urgency::AGE => urgency.age = weight,
+                Urgency::ANNOTATIONS => urgency.annotations = weight,
+                Urgency::PROJECT => urgency.project = weight,
+                Urgency::BLOCKED => urgency.blocked = weight,
+                Urgency::BLOCKING => urgency.blocking = weight,
+                _ if Project::new(name).is_some() || Context::new(name).is_some() => {
+                    Urgency::set_weight(&mut urgency.tags, name, weight)
+                }
+                _ => match name.strip_suffix(':').filter(|key| !key.is_empty()) {
+                    Some(key) => Urgency::set_weight(&mut urgency.dates, key, weight),
+                    None => return Err(InvalidUrgencyWeight(line.to_owned())),
+                },
+            }
+        }
+
+        Ok(urgency)
+    }
+
+    fn set_weight(weights: &mut Vec<(String, f64)>, name: &str, weight: f64) {
+        match weights.iter_mut().find(|(n, _)| n == name) {
+            Some((_, w)) => *w = weight,
+            None => weights.push((name.to_owned(), weight)),
+        }
+    }
+
+    // Blocked and blocking tasks, and virtual tags, are judged from the dependencies and tag dates.
+    pub fn score(&self, task: &Task, dependencies: &Dependencies, tag_dates: &TagDates) -> f64 {
+        let mut score = 0.0;
+
+        // `(A)` counts fully, down to `(Z)` counting for a 26th.
+        if let Some(pri) = task.get_priority() {
+            score += self.priority * f64::from(b'Z' - pri.as_u8() + 1) / 26.0;
+        }
+        if let Some(entry) = task.get_entry().and_then(|e| Date::from_abs(e.as_str())) {
+            let age = self.now.days_since(&entry).max(0.0);
+            score += self.age * (age / Urgency::MAX_AGE).min(1.0);
+        }
+        if !task.get_annotations().is_empty() {
+            score += self.annotations;
+        }
+        if task
+            .iter()
+            .any(|(token, _)| matches!(token, Token::Project(_)))
+        {
+            score += self.project;
+        }
+        if dependencies.is_blocked(task) {
+            score += self.blocked;
+        }
+        if dependencies.is_blocking(task) {
+            score += self.blocking;
+        }
+
+        for (key, weight) in &self.dates {
+            if let Some(date) = task
+                .get_value(&Key::new(key))
+                .and_then(|v| Date::from_abs(v.as_str()))
+            {
+                score += weight * Urgency::proximity(self.now.days_until_end(&date));
+            }
+        }
+
+        for (name, weight) in &self.tags {
+            let has_tag = match (
+                VirtualTag::new(name),
+                Project::new(name),
+                Context::new(name),
+            ) {
+                (Some(tag), Some(proj), _) => {
+                    tag.is_set(task, tag_dates, dependencies) || task.has_project(&proj)
+                }
+                (_, Some(proj), _) => task.has_project(&proj),
+                (_, None, Some(ctx)) => task.has_context(&ctx),
+                (_, None, None) => false,
+            };
+            if has_tag {
+                score += weight;
+            }
+        }
+
+        score
+    }
+
+    // From 0.2 for dates two weeks or more away, rising linearly to 1.0 for dates a week overdue.
+    fn proximity(days_left: f64) -> f64 {
+        match -days_left {
+            overdue if overdue >= 7.0 => 1.0,
+            overdue if overdue >= -14.0 => (overdue + 14.0) * 0.8 / 21.0 + 0.2,
+            _ => 0.2,
+        }
+    }
+}
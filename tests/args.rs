@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use chore::*;
 
 #[test]
@@ -203,3 +205,134 @@ fn general() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn reports() -> Result<()> {
+    let reports = vec![
+        File {
+            name: "standup".to_string(),
+            content: concat!(
+                "# Pending work, most important first\n",
+                "-+done\n",
+                "  # sorting\n",
+                "list sort:pri group:project\n",
+                "     limit:2\n",
+            )
+            .to_string(),
+        },
+        File {
+            name: "finished".to_string(),
+            content: "+done list annotations:hide\n".to_string(),
+        },
+        File {
+            name: "purge".to_string(),
+            content: "+done delete\n".to_string(),
+        },
+        File {
+            name: "list".to_string(),
+            content: "+done list\n".to_string(),
+        },
+        File {
+            name: "unfinished".to_string(),
+            content: "# Missing a command\n-+done\n".to_string(),
+        },
+    ];
+    let tasks = concat!(
+        "(M) 2001-02-03 @home +chore add tests\n",
+        "add task due:2002-03-04T05:06:07 +chore\n",
+        "x 2001-02-03 (H) 2001-01-02 @work issue:123 | 2001-02-03 shipped\n",
+        "(A) urgent task\n",
+    );
+
+    for (args, expect) in &[
+        (
+            vec!["standup"],
+            concat!(
+                "+chore 1\n",
+                "  1 (M) 2001-02-03 @home +chore add tests\n",
+                "none 1\n",
+                "  4 (A) urgent task\n",
+            ),
+        ),
+        (
+            vec!["standup", "limit:5", "group:context"],
+            concat!(
+                "@home 1\n",
+                "  1 (M) 2001-02-03 @home +chore add tests\n",
+                "none 2\n",
+                "  4 (A) urgent task\n",
+                "  2 add task due:2002-03-04T05:06:07 +chore\n",
+            ),
+        ),
+        (
+            vec!["+chore", "standup"],
+            concat!(
+                "+chore 2\n",
+                "  1 (M) 2001-02-03 @home +chore add tests\n",
+                "  2 add task due:2002-03-04T05:06:07 +chore\n",
+            ),
+        ),
+        (
+            vec!["finished"],
+            "3 x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+        ),
+        (
+            vec!["+chore", "list"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+                "2 add task due:2002-03-04T05:06:07 +chore\n",
+            ),
+        ),
+        (
+            vec!["reports"],
+            concat!(
+                "finished\n",
+                "list\n",
+                "purge\n",
+                "standup     Pending work, most important first\n",
+                "unfinished  Missing a command\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            reports: reports.clone(),
+            ..Default::default()
+        };
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for (args, expect) in &[
+        (
+            vec!["purge"],
+            ReportExpectsListCommand("purge".to_string()),
+        ),
+        (
+            vec!["unfinished", "+chore"],
+            ReportExpectsListCommand("unfinished".to_string()),
+        ),
+        (
+            vec!["standup", "limit:0"],
+            InvalidCommandArg("limit:0".to_string()),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            reports: reports.clone(),
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+
+    Ok(())
+}
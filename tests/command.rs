@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use chore::*;
 
 #[test]
@@ -81,7 +83,7 @@ fn general() -> Result<()> {
             vec!["projects"],
             None,
             Output::JustPrint {
-                stdout: concat!("+chore\n",).to_string(),
+                stdout: concat!("+chore 1\n",).to_string(),
             },
         ),
         (
@@ -93,7 +95,54 @@ fn general() -> Result<()> {
             vec!["contexts"],
             None,
             Output::JustPrint {
-                stdout: concat!("@home\n", "@work\n",).to_string(),
+                stdout: concat!("@home 1\n", "@work 1\n",).to_string(),
+            },
+        ),
+        (
+            concat!(
+                "a +work.infra.k8s +work.infra @office\n",
+                "b +work-x +home\n",
+                "c +work.docs @office.desk\n",
+                "d +work\n",
+            ),
+            vec!["projects"],
+            None,
+            Output::JustPrint {
+                stdout: concat!(
+                    "+home 1\n",
+                    "+work 3\n",
+                    "  +work.docs 1\n",
+                    "  +work.infra 1\n",
+                    "    +work.infra.k8s 1\n",
+                    "+work-x 1\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "a +work.infra.k8s +work.infra @office\n",
+                "b +work-x +home\n",
+                "c +work.docs @office.desk\n",
+                "d +work\n",
+            ),
+            vec!["+work.infra", "contexts"],
+            None,
+            Output::JustPrint {
+                stdout: concat!("@office 1\n").to_string(),
+            },
+        ),
+        (
+            concat!(
+                "a +work.infra.k8s +work.infra @office\n",
+                "b +work-x +home\n",
+                "c +work.docs @office.desk\n",
+                "d +work\n",
+            ),
+            vec!["contexts"],
+            None,
+            Output::JustPrint {
+                stdout: concat!("@office 2\n", "  @office.desk 1\n").to_string(),
             },
         ),
         (
@@ -299,6 +348,70 @@ fn general() -> Result<()> {
                 ).to_string(),
             },
         ),
+        (
+            concat!(
+                "a due:2001-02-01\n",
+                "b due:2001-02-03 | note\n",
+                "c due:2001-02-20 recur:1w\n",
+                "x d due:2001-01-01\n",
+            ),
+            vec!["tags"],
+            None,
+            Output::JustPrint {
+                stdout: concat!(
+                    "+overdue 1\n",
+                    "+today 1\n",
+                    "+week 2\n",
+                    "+soon 1\n",
+                    "+annotated 1\n",
+                    "+recurring 1\n",
+                    "+ready 3\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "call bob +work | 2001-01-15 left a message | 2001-02-02 no answer\n",
+                "buy milk\n",
+            ),
+            vec!["list", "annotations:indent"],
+            None,
+            Output::JustPrint {
+                stdout: concat!(
+                    "1 call bob +work\n",
+                    "    2001-01-15 left a message\n",
+                    "    2001-02-02 no answer\n",
+                    "2 buy milk\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "call bob +work | 2001-01-15 left a message | 2001-02-02 no answer\n",
+                "buy milk\n",
+            ),
+            vec!["list", "annotations:hide"],
+            None,
+            Output::JustPrint {
+                stdout: concat!(
+                    "1 call bob +work\n",
+                    "2 buy milk\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "call bob +work | 2001-01-15 left a message\n",
+            ),
+            vec!["list", "annotations:inline"],
+            None,
+            Output::JustPrint {
+                stdout: "1 call bob +work | 2001-01-15 left a message\n".to_string(),
+            },
+        ),
     ] {
         let config = Config {
             now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
@@ -327,7 +440,13 @@ fn invalid() -> Result<()> {
             "",
             vec!["list", "+done"],
             None,
-            CmdDisallowsMod,
+            InvalidCommandArg("+done".to_owned()),
+        ),
+        (
+            "",
+            vec!["list", "annotations:below"],
+            None,
+            InvalidCommandArg("annotations:below".to_owned()),
         ),
         (
             "",
@@ -347,6 +466,12 @@ fn invalid() -> Result<()> {
             None,
             CmdDisallowsMod,
         ),
+        (
+            "",
+            vec!["tags", "+done"],
+            None,
+            CmdDisallowsMod,
+        ),
         (
             "",
             vec!["delete", "+done"],
@@ -389,3 +514,864 @@ fn invalid() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn dependencies() -> Result<()> {
+    for (tasks, args, expect) in &[
+        (
+            concat!(
+                "deploy depends:review\n",
+                "review id:review\n",
+            ),
+            vec!["id:review", "modify", "+done"],
+            Output::WriteFiles {
+                stdout: concat!(
+                    "DEL review id:review\n",
+                    "ADD x review id:review\n",
+                    "READY deploy depends:review\n",
+                )
+                .to_string(),
+                confirm: false,
+                tasks: concat!(
+                    "deploy depends:review\n",
+                    "x review id:review\n",
+                )
+                .to_string(),
+                undo: concat!(
+                    "---\n",
+                    "DEL review id:review\n",
+                    "ADD x review id:review\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "deploy depends:review,test\n",
+                "review id:review\n",
+                "test id:test\n",
+            ),
+            vec!["id:review", "modify", "+done"],
+            Output::WriteFiles {
+                stdout: concat!(
+                    "DEL review id:review\n",
+                    "ADD x review id:review\n",
+                )
+                .to_string(),
+                confirm: false,
+                tasks: concat!(
+                    "deploy depends:review,test\n",
+                    "test id:test\n",
+                    "x review id:review\n",
+                )
+                .to_string(),
+                undo: concat!(
+                    "---\n",
+                    "DEL review id:review\n",
+                    "ADD x review id:review\n",
+                )
+                .to_string(),
+            },
+        ),
+        (
+            concat!(
+                "deploy depends:review\n",
+                "review id:review\n",
+            ),
+            vec!["id:review", "delete"],
+            Output::WriteFiles {
+                stdout: concat!(
+                    "WARN deploy depends:review\n",
+                    "DEL review id:review\n",
+                )
+                .to_string(),
+                confirm: true,
+                tasks: "deploy depends:review\n".to_string(),
+                undo: concat!(
+                    "---\n",
+                    "DEL review id:review\n",
+                )
+                .to_string(),
+            },
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            ..Default::default()
+        };
+
+        let actual = chore::run(config)?;
+        assert_eq!(actual, *expect);
+    }
+
+    for (tasks, args, expect) in &[
+        (
+            concat!(
+                "design id:design\n",
+                "review id:review depends:design\n",
+            ),
+            vec!["id:design", "modify", "depends:review"],
+            DependencyCycle("design -> review -> design".to_owned()),
+        ),
+        (
+            "",
+            vec!["add", "loop", "id:loop", "depends:loop"],
+            DependencyCycle("loop -> loop".to_owned()),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            ..Default::default()
+        };
+
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+    Ok(())
+}
+
+#[test]
+fn urgency() -> Result<()> {
+    let tasks = concat!(
+        "(A) 2001-01-01 write report +work\n",
+        "call bob due:2001-02-03\n",
+        "deploy id:deploy\n",
+        "idle chat\n",
+        "review depends:deploy\n",
+        "x done task\n",
+    );
+    for (args, weights, expect) in &[
+        (
+            vec!["next"],
+            None,
+            concat!(
+                "2  8.4 call bob due:2001-02-03\n",
+                "3  8.0 deploy id:deploy\n",
+                "1  7.2 (A) 2001-01-01 write report +work\n",
+                "4  0.0 idle chat\n",
+                "5 -5.0 review depends:deploy\n",
+            ),
+        ),
+        (
+            vec!["next"],
+            Some("# weights\n+work 5\npriority 0\ndue: 0\nblocking 0\n"),
+            concat!(
+                "1  6.2 (A) 2001-01-01 write report +work\n",
+                "2  0.0 call bob due:2001-02-03\n",
+                "3  0.0 deploy id:deploy\n",
+                "4  0.0 idle chat\n",
+                "5 -5.0 review depends:deploy\n",
+            ),
+        ),
+        (
+            vec!["/de/", "next"],
+            None,
+            concat!(
+                "3  8.0 deploy id:deploy\n",
+                "5 -5.0 review depends:deploy\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            urgency: weights.map(|s| s.to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for (weights, expect) in &[
+        (
+            "priority high",
+            InvalidUrgencyWeight("priority high".to_owned()),
+        ),
+        ("urgent 3", InvalidUrgencyWeight("urgent 3".to_owned())),
+        ("age", InvalidUrgencyWeight("age".to_owned())),
+    ] {
+        let config = Config {
+            args: vec!["next".to_owned()],
+            urgency: Some(weights.to_string()),
+            ..Default::default()
+        };
+
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+    Ok(())
+}
+
+#[test]
+fn sort() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["list", "sort:pri"],
+            concat!(
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "4 x 2001-02-01 (C) d task\n",
+                "3 c task due:2001-02-01T12 size:big\n",
+            ),
+        ),
+        (
+            vec!["list", "sort:due-"],
+            concat!(
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "3 c task due:2001-02-01T12 size:big\n",
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+                "4 x 2001-02-01 (C) d task\n",
+            ),
+        ),
+        (
+            vec!["list", "sort:size"],
+            concat!(
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "3 c task due:2001-02-01T12 size:big\n",
+                "4 x 2001-02-01 (C) d task\n",
+            ),
+        ),
+        (
+            vec!["list", "sort:project,pri-"],
+            concat!(
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "4 x 2001-02-01 (C) d task\n",
+                "3 c task due:2001-02-01T12 size:big\n",
+            ),
+        ),
+        (
+            vec!["list", "sort:entry+"],
+            concat!(
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+                "3 c task due:2001-02-01T12 size:big\n",
+                "4 x 2001-02-01 (C) d task\n",
+            ),
+        ),
+        (
+            vec!["-+done", "list", "sort:urgency-"],
+            concat!(
+                "3 c task due:2001-02-01T12 size:big\n",
+                "1 (B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                "2 (A) 2001-01-10 a task size:9 +alpha\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "(B) 2001-01-05 b task due:2001-03-01 size:10 +zeta\n",
+                    "(A) 2001-01-10 a task size:9 +alpha\n",
+                    "c task due:2001-02-01T12 size:big\n",
+                    "x 2001-02-01 (C) d task\n",
+                )
+                .to_owned(),
+            ),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for arg in &["sort:", "sort:pri,", "sort:-"] {
+        let config = Config {
+            args: vec!["list".to_owned(), arg.to_string()],
+            ..Default::default()
+        };
+
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            format!("{:?}", actual),
+            format!("{:?}", InvalidCommandArg(arg.to_string()))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn group() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["list", "group:project"],
+            concat!(
+                "+alpha 2\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "  2 (A) a task due:2001-03-01T09 size:9 +alpha\n",
+                "+zeta 1\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "none 2\n",
+                "  3 c task due:2001-02-01 size:big @home\n",
+                "  4 d task\n",
+            ),
+        ),
+        (
+            vec!["list", "group:context"],
+            concat!(
+                "@home 2\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "  3 c task due:2001-02-01 size:big @home\n",
+                "none 2\n",
+                "  2 (A) a task due:2001-03-01T09 size:9 +alpha\n",
+                "  4 d task\n",
+            ),
+        ),
+        (
+            vec!["list", "group:pri", "sort:pri"],
+            concat!(
+                "(A) 1\n",
+                "  2 (A) a task due:2001-03-01T09 size:9 +alpha\n",
+                "(B) 1\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "none 2\n",
+                "  3 c task due:2001-02-01 size:big @home\n",
+                "  4 d task\n",
+            ),
+        ),
+        (
+            vec!["list", "group:due"],
+            concat!(
+                "due:2001-02-01 1\n",
+                "  3 c task due:2001-02-01 size:big @home\n",
+                "due:2001-03-01 2\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "  2 (A) a task due:2001-03-01T09 size:9 +alpha\n",
+                "none 1\n",
+                "  4 d task\n",
+            ),
+        ),
+        (
+            vec!["list", "group:size"],
+            concat!(
+                "size:9 1\n",
+                "  2 (A) a task due:2001-03-01T09 size:9 +alpha\n",
+                "size:10 1\n",
+                "  1 (B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                "size:big 1\n",
+                "  3 c task due:2001-02-01 size:big @home\n",
+                "none 1\n",
+                "  4 d task\n",
+            ),
+        ),
+        (vec!["/nothing/", "list", "group:project"], ""),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "(B) b task due:2001-03-01 size:10 +zeta +alpha @home\n",
+                    "(A) a task due:2001-03-01T09 size:9 +alpha\n",
+                    "c task due:2001-02-01 size:big @home\n",
+                    "d task\n",
+                )
+                .to_owned(),
+            ),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // Weeks and quarters keep sections of their own, placed by the day they start.
+    let config = Config {
+        args: vec!["list".to_owned(), "group:due".to_owned()],
+        tasks: Some(
+            concat!(
+                "a due:2021-03-02\n",
+                "b due:2021-Q1\n",
+                "c due:2021-03-01\n",
+                "d due:2021-W09\n",
+                "e due:2021-03-01T10\n",
+                "f due:someday\n",
+            )
+            .to_owned(),
+        ),
+        date_keys: Some("due:\n".to_owned()),
+        ..Default::default()
+    };
+    let expect = concat!(
+        "due:2021-Q1 1\n",
+        "  2 b due:2021-Q1\n",
+        "due:2021-03-01 2\n",
+        "  3 c due:2021-03-01\n",
+        "  5 e due:2021-03-01T10\n",
+        "due:2021-W09 1\n",
+        "  4 d due:2021-W09\n",
+        "due:2021-03-02 1\n",
+        "  1 a due:2021-03-02\n",
+        "due:someday 1\n",
+        "  6 f due:someday\n",
+    );
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+        _ => panic!("expected JustPrint"),
+    }
+
+    let config = Config {
+        args: vec!["list".to_owned(), "group:".to_owned()],
+        ..Default::default()
+    };
+    let actual = match chore::run(config) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        format!("{:?}", actual),
+        format!("{:?}", InvalidCommandArg("group:".to_owned()))
+    );
+    Ok(())
+}
+
+#[test]
+fn table() -> Result<()> {
+    for (args, width, expect) in &[
+        (
+            vec!["list", "columns:default"],
+            None,
+            concat!(
+                "nr  pri  due  age  projects      body\n",
+                " 1  B    3w   4w   +zeta +alpha  b task\n",
+                " 2  A    -1d  3w   +alpha        a task with a rather long body\n",
+                " 3       0d                      c 日本語 task\n",
+                " 4            2w                 d task\n",
+            ),
+        ),
+        (
+            vec!["list", "columns:nr,size:,body", "sort:size"],
+            None,
+            concat!(
+                "nr  size  body\n",
+                " 2  9     a task with a rather long body\n",
+                " 1  10    b task\n",
+                " 3  big   c 日本語 task\n",
+                " 4        d task\n",
+            ),
+        ),
+        (
+            vec!["/task/", "list", "columns:nr,due.rel:,body"],
+            Some(24),
+            concat!(
+                "nr  due  body\n",
+                " 1  3w   b task\n",
+                " 2  -1d  a task with a\n",
+                "         rather long\n",
+                "         body\n",
+                " 3  0d   c 日本語 task\n",
+                " 4       d task\n",
+            ),
+        ),
+        (
+            vec!["1,2,3", "list", "columns:nr,body,pri", "overflow:truncate"],
+            Some(21),
+            concat!(
+                "nr  body          pri\n",
+                " 1  b task        B\n",
+                " 2  a task with…  A\n",
+                " 3  c 日本語 ta…\n",
+            ),
+        ),
+        (
+            vec!["1,2", "list", "columns:nr,body", "group:project"],
+            None,
+            concat!(
+                "+alpha 2\n",
+                "  nr  body\n",
+                "   1  b task\n",
+                "   2  a task with a rather long body\n",
+                "+zeta 1\n",
+                "  nr  body\n",
+                "   1  b task\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "(B) 2001-01-05 b task due:2001-03-01 size:10 +zeta +alpha\n",
+                    "(A) 2001-01-10 a task with a rather long body due:2001-02-01T12 size:9 +alpha\n",
+                    "c 日本語 task due:2001-02-03 size:big\n",
+                    "2001-01-20 d task\n",
+                )
+                .to_owned(),
+            ),
+            date_keys: Some("due:\n".to_owned()),
+            width: *width,
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for (arg, expect) in &[
+        ("columns:", InvalidCommandArg("columns:".to_owned())),
+        ("columns:nr,bogus", InvalidColumn("bogus".to_owned())),
+        ("columns:size.rel:", ModExpectsDateKey("size.rel:".to_owned())),
+        ("overflow:hide", InvalidCommandArg("overflow:hide".to_owned())),
+    ] {
+        let config = Config {
+            args: vec!["list".to_owned(), arg.to_string()],
+            date_keys: Some("due:\n".to_owned()),
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+    Ok(())
+}
+
+#[test]
+fn dates() -> Result<()> {
+    let tasks = concat!(
+        "(A) 2001-01-20 pay rent due:2001-02-01 size:2001-01-01\n",
+        "file taxes due:2001-02-03 wait:2001-01-30\n",
+        "call back due:2001-02-03T10 +phone\n",
+        "plan trip due:2001-03-01\n",
+        "x 2001-02-02 2001-01-02 paid bill due:2001-01-31\n",
+        "undated task | 2001-01-27 note\n",
+        "send report due:2001-02\n",
+        "ring back due:2001-02-03T04:04\n",
+        "book table due:2001-02-03T04:05\n",
+        "renew lease due:2001-01\n",
+    );
+    for (args, expect) in &[
+        (
+            vec!["list", "dates:relative"],
+            concat!(
+                " 1 (A) 2w ago pay rent due:overdue 2d size:2001-01-01\n",
+                " 2 file taxes due:today wait:4d ago\n",
+                " 3 call back due:in 5h +phone\n",
+                " 4 plan trip due:in 3w\n",
+                " 5 x 1d ago 4w ago paid bill due:3d ago\n",
+                " 6 undated task | 2001-01-27 note\n",
+                " 7 send report due:this month\n",
+                " 8 ring back due:overdue 6s\n",
+                " 9 book table due:now\n",
+                "10 renew lease due:overdue 3d\n",
+            ),
+        ),
+        (
+            vec!["1,4", "list", "dates:both"],
+            concat!(
+                "1 (A) 2001-01-20 [2w ago] pay rent due:2001-02-01 [overdue 2d] size:2001-01-01\n",
+                "4 plan trip due:2001-03-01 [in 3w]\n",
+            ),
+        ),
+        (
+            vec!["2", "list", "dates:stored"],
+            "2 file taxes due:2001-02-03 wait:2001-01-30\n",
+        ),
+        (
+            vec!["2", "list", "dates:relative", "annotations:indent"],
+            "2 file taxes due:today wait:4d ago\n",
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_owned()),
+            date_keys: Some("due:\nwait:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // Overdue dates stand out in red, dates due today in yellow.
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["1,2".to_owned(), "list".to_owned(), "dates:relative".to_owned()],
+        tasks: Some(tasks.to_owned()),
+        date_keys: Some("due:\nwait:\n".to_owned()),
+        is_terminal: true,
+        ..Default::default()
+    };
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => {
+            assert!(stdout.contains("\x1b[38;5;196moverdue 2d"));
+            assert!(stdout.contains("\x1b[38;5;11mtoday"));
+        }
+        _ => panic!("expected JustPrint"),
+    }
+
+    // The theme may restyle them.
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["1,2".to_owned(), "list".to_owned(), "dates:both".to_owned()],
+        tasks: Some(tasks.to_owned()),
+        date_keys: Some("due:\nwait:\n".to_owned()),
+        theme: Some("overdue 33\ncurrent 21\nseparator 240\n".to_owned()),
+        is_terminal: true,
+        ..Default::default()
+    };
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => {
+            assert!(stdout.contains("\x1b[38;5;240m [\x1b[38;5;33moverdue 2d\x1b[38;5;240m]"));
+            assert!(stdout.contains("\x1b[38;5;21mtoday"));
+        }
+        _ => panic!("expected JustPrint"),
+    }
+
+    let config = Config {
+        args: vec!["list".to_owned(), "dates:human".to_owned()],
+        ..Default::default()
+    };
+    let actual = match chore::run(config) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        format!("{:?}", actual),
+        format!("{:?}", InvalidCommandArg("dates:human".to_owned()))
+    );
+    Ok(())
+}
+
+#[test]
+fn calendar() -> Result<()> {
+    let tasks = concat!(
+        "pay rent due:2001-02-01\n",
+        "call back due:2001-02-03T10\n",
+        "x 2001-02-02 paid bill due:2001-02-02\n",
+        "trip scheduled:2001-W07\n",
+        "plan due:2001-03-05\n",
+        "undated task\n",
+    );
+    for (args, expect) in &[
+        (
+            vec!["calendar"],
+            concat!(
+                "2001-02\n",
+                "Mo  Tu  We  Th  Fr  Sa  Su\n",
+                "             1!  2x  3*  4\n",
+                " 5   6   7   8   9  10  11\n",
+                "12* 13* 14* 15* 16* 17* 18*\n",
+                "19  20  21  22  23  24  25\n",
+                "26  27  28\n",
+            ),
+        ),
+        (
+            vec!["calendar", "keys:due", "from:2001-03", "months:2"],
+            concat!(
+                "2001-03\n",
+                "Mo  Tu  We  Th  Fr  Sa  Su\n",
+                "             1   2   3   4\n",
+                " 5*  6   7   8   9  10  11\n",
+                "12  13  14  15  16  17  18\n",
+                "19  20  21  22  23  24  25\n",
+                "26  27  28  29  30  31\n",
+                "\n",
+                "2001-04\n",
+                "Mo  Tu  We  Th  Fr  Sa  Su\n",
+                "                         1\n",
+                " 2   3   4   5   6   7   8\n",
+                " 9  10  11  12  13  14  15\n",
+                "16  17  18  19  20  21  22\n",
+                "23  24  25  26  27  28  29\n",
+                "30\n",
+            ),
+        ),
+        (
+            vec!["1,3", "calendar", "tasks:show"],
+            concat!(
+                "2001-02\n",
+                "Mo  Tu  We  Th  Fr  Sa  Su\n",
+                "             1!  2x  3   4\n",
+                " 5   6   7   8   9  10  11\n",
+                "12  13  14  15  16  17  18\n",
+                "19  20  21  22  23  24  25\n",
+                "26  27  28\n",
+                "\n",
+                "2001-02-01 Thu\n",
+                "  1 pay rent due:2001-02-01\n",
+                "2001-02-02 Fri\n",
+                "  3 x 2001-02-02 paid bill due:2001-02-02\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_owned()),
+            date_keys: Some("due:\nscheduled:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // Marks and today take their colours from the theme.
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["1,2,3".to_owned(), "calendar".to_owned()],
+        tasks: Some(tasks.to_owned()),
+        date_keys: Some("due:\n".to_owned()),
+        theme: Some("overdue 33\ncompleted 34\ncurrent 35\npending 36\n".to_owned()),
+        is_terminal: true,
+        ..Default::default()
+    };
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => {
+            assert!(stdout.contains("\x1b[38;5;33m 1! \x1b[38;5;34m 2x "));
+            assert!(stdout.contains("\x1b[38;5;35m 3\x1b[38;5;36m*"));
+        }
+        _ => panic!("expected JustPrint"),
+    }
+
+    for arg in &["months:0", "keys:size", "tasks:maybe", "from:nope", "week"] {
+        let config = Config {
+            args: vec!["calendar".to_owned(), arg.to_string()],
+            date_keys: Some("due:\n".to_owned()),
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            format!("{:?}", actual),
+            format!("{:?}", InvalidCommandArg(arg.to_string()))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn agenda() -> Result<()> {
+    let tasks = concat!(
+        "pay rent due:2001-02-01\n",
+        "call back due:2001-02-03T10\n",
+        "x 2001-02-02 paid bill due:2001-02-02\n",
+        "trip scheduled:2001-W06\n",
+        "plan due:2001-03-05\n",
+        "undated task\n",
+        "x 2001-02-03 tidy up due:2001-02-04\n",
+        "water plants wait:2001-01-20\n",
+    );
+    for (args, expect) in &[
+        (
+            vec!["agenda", "days:3"],
+            concat!(
+                "overdue 1\n",
+                "  1 pay rent due:2001-02-01\n",
+                "2001-02-03 Sat 1\n",
+                "  2 call back due:2001-02-03T10\n",
+                "2001-02-04 Sun 0\n",
+                "2001-02-05 Mon 1\n",
+                "  4 trip scheduled:2001-W06\n",
+                "no date 1\n",
+                "  6 undated task\n",
+            ),
+        ),
+        (
+            vec!["agenda", "days:1", "keys:due"],
+            concat!(
+                "overdue 1\n",
+                "  1 pay rent due:2001-02-01\n",
+                "2001-02-03 Sat 1\n",
+                "  2 call back due:2001-02-03T10\n",
+                "no date 1\n",
+                "  6 undated task\n",
+            ),
+        ),
+        (
+            vec!["+done", "agenda", "days:2"],
+            concat!(
+                "overdue 0\n",
+                "2001-02-03 Sat 0\n",
+                "2001-02-04 Sun 1\n",
+                "  7 x 2001-02-03 tidy up due:2001-02-04\n",
+                "no date 0\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_owned()),
+            date_keys: Some("due:\nscheduled:\nwait:\n".to_owned()),
+            default_filters: vec![File {
+                name: "hide-completed".to_owned(),
+                content: "-+done".to_owned(),
+            }],
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for arg in &["days:0", "keys:size", "week"] {
+        let config = Config {
+            args: vec!["agenda".to_owned(), arg.to_string()],
+            date_keys: Some("due:\n".to_owned()),
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            format!("{:?}", actual),
+            format!("{:?}", InvalidCommandArg(arg.to_string()))
+        );
+    }
+    Ok(())
+}
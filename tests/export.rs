@@ -0,0 +1,179 @@
+#![allow(clippy::useless_concat)]
+
+use chore::*;
+
+#[test]
+fn general() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["csv"],
+            concat!(
+                "nr,done,end,pri,entry,body\n",
+                "1,,,M,2001-02-03,add tests\n",
+                "2,,,,,\"add, then \"\"quote\"\" task\"\n",
+                "3,x,2001-02-03,H,2001-01-02,\n",
+            ),
+        ),
+        (
+            vec!["tsv"],
+            concat!(
+                "nr\tdone\tend\tpri\tentry\tbody\n",
+                "1\t\t\tM\t2001-02-03\tadd tests\n",
+                "2\t\t\t\t\tadd, then \"quote\" task\n",
+                "3\tx\t2001-02-03\tH\t2001-01-02\t\n",
+            ),
+        ),
+        (
+            vec!["+done", "csv", "nr", "projects", "contexts", "issue:"],
+            concat!(
+                "nr,projects,contexts,issue\n",
+                "3,,@work,123\n",
+            ),
+        ),
+        (
+            vec!["+done", "csv", "pri", "pri:", "end:"],
+            concat!("pri,pri,end\n", "H,,\n"),
+        ),
+        (
+            vec!["csv", "body", "projects", "annotations"],
+            concat!(
+                "body,projects,annotations\n",
+                "add tests,+chore +test +more,see docs | and +more\n",
+                "\"add, then \"\"quote\"\" task\",,\n",
+                ",,\n",
+            ),
+        ),
+        (
+            vec!["2", "csv", "due:", "due.full:", "entry.full", "missing:"],
+            concat!(
+                "due,due,entry,missing\n",
+                "2002-03-04T05,2002-03-04T05:00:00,,\n",
+            ),
+        ),
+        (
+            vec!["1,2", "csv", "nr", "due.rel:", "age"],
+            concat!("nr,due,age\n", "1,,0d\n", "2,1y,\n",),
+        ),
+        (
+            vec!["markdown"],
+            concat!(
+                "- [ ] (M) @home +chore add tests +test\n",
+                "  - see docs\n",
+                "  - and +more\n",
+                "- [ ] add, then \"quote\" task due:2002-03-04T05\n",
+                "- [x] (H) @work issue:123\n",
+            ),
+        ),
+        (
+            vec!["markdown", "group:project"],
+            concat!(
+                "## +chore\n",
+                "- [ ] (M) @home +chore add tests +test\n",
+                "  - see docs\n",
+                "  - and +more\n",
+                "## +more\n",
+                "- [ ] (M) @home +chore add tests +test\n",
+                "  - see docs\n",
+                "  - and +more\n",
+                "## +test\n",
+                "- [ ] (M) @home +chore add tests +test\n",
+                "  - see docs\n",
+                "  - and +more\n",
+                "## none\n",
+                "- [ ] add, then \"quote\" task due:2002-03-04T05\n",
+                "- [x] (H) @work issue:123\n",
+            ),
+        ),
+        (
+            vec!["org", "deadline:due"],
+            concat!(
+                "* TODO [#M] add tests :@home:chore:test:more:\n",
+                "  see docs\n",
+                "  and +more\n",
+                "* TODO add, then \"quote\" task\n",
+                "  DEADLINE: <2002-03-04 Mon 05:00>\n",
+                "* DONE [#H] issue:123 :@work:\n",
+                "  CLOSED: [2001-02-03 Sat]\n",
+            ),
+        ),
+        (
+            vec!["-/quote/", "org", "group:project", "scheduled:due"],
+            concat!(
+                "* +chore\n",
+                "** TODO [#M] add tests :@home:chore:test:more:\n",
+                "   see docs\n",
+                "   and +more\n",
+                "* +more\n",
+                "** TODO [#M] add tests :@home:chore:test:more:\n",
+                "   see docs\n",
+                "   and +more\n",
+                "* +test\n",
+                "** TODO [#M] add tests :@home:chore:test:more:\n",
+                "   see docs\n",
+                "   and +more\n",
+                "* none\n",
+                "** DONE [#H] issue:123 :@work:\n",
+                "   CLOSED: [2001-02-03 Sat]\n",
+            ),
+        ),
+        (
+            vec!["1", "tsv", "nr pri  entry.full"],
+            concat!(
+                "nr\tpri\tentry\n",
+                "1\tM\t2001-02-03T00:00:00\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "(M) 2001-02-03 @home +chore add tests +test | see docs | and +more\n",
+                    "add, then \"quote\" task due:2002-03-04T05\n",
+                    "x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+                )
+                .to_string(),
+            ),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        let expect = Output::JustPrint {
+            stdout: expect.to_string(),
+        };
+        assert_eq!(chore::run(config)?, expect);
+    }
+    Ok(())
+}
+
+#[test]
+fn invalid() -> Result<()> {
+    for (args, expect) in &[
+        (vec!["csv", "nope"], InvalidColumn("nope".to_owned())),
+        (vec!["csv", ":"], InvalidColumn(":".to_owned())),
+        (vec!["csv", "issue.full:"], ModExpectsDateKey("issue.full:".to_owned())),
+        (vec!["tsv", "pri.full"], ModExpectsDateKey("pri.full".to_owned())),
+        (vec!["csv", "issue.rel:"], ModExpectsDateKey("issue.rel:".to_owned())),
+        (vec!["tsv", "due.nope:"], InvalidMod("due.nope:".to_owned())),
+        (vec!["org", "nope"], InvalidCommandArg("nope".to_owned())),
+        (vec!["org", "group:nope"], InvalidCommandArg("group:nope".to_owned())),
+        (vec!["org", "deadline:issue"], ArgExpectsDateKey("deadline:issue".to_owned())),
+        (vec!["markdown", "deadline:due"], InvalidCommandArg("deadline:due".to_owned())),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some("foo\n".to_string()),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use chore::*;
 
 #[test]
@@ -334,6 +336,90 @@ fn general() -> Result<()> {
             vec!["due.in:2003"],
             "",
         ),
+        (
+            vec!["entry.in:sow"],
+            "",
+        ),
+        (
+            vec!["entry.before:eow"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+                "3 x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+            ),
+        ),
+        (
+            vec!["entry.after:last monday"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+            ),
+        ),
+        (
+            vec!["due.before:in 2 years"],
+            concat!(
+                "2 add task due:2002-03-04T05:06:07\n",
+            ),
+        ),
+        (
+            vec!["due.in:2002-W10"],
+            concat!(
+                "2 add task due:2002-03-04T05:06:07\n",
+            ),
+        ),
+        (
+            vec!["due.in:2002-W09"],
+            "",
+        ),
+        (
+            vec!["due.in:2002-Q1"],
+            concat!(
+                "2 add task due:2002-03-04T05:06:07\n",
+            ),
+        ),
+        (
+            vec!["due.before:2002-Q1"],
+            "",
+        ),
+        (
+            vec!["entry.in:thisweek"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+            ),
+        ),
+        (
+            vec!["due.between:2002-03-03..2002-03-04"],
+            concat!(
+                "2 add task due:2002-03-04T05:06:07\n",
+            ),
+        ),
+        (
+            vec!["due.between:2002-03-05.."],
+            "",
+        ),
+        (
+            vec!["due.between:..2002-03"],
+            concat!(
+                "2 add task due:2002-03-04T05:06:07\n",
+            ),
+        ),
+        (
+            vec!["entry.between:2001-01-01..today"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+                "3 x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+            ),
+        ),
+        (
+            vec!["entry.between:2001-01-03..tomorrow"],
+            concat!(
+                "1 (M) 2001-02-03 @home +chore add tests\n",
+            ),
+        ),
+        (
+            vec!["end.between:sow..eow"],
+            concat!(
+                "3 x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+            ),
+        ),
     ] {
         let config = Config {
             now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
@@ -647,6 +733,58 @@ fn invalid() -> Result<()> {
             vec!["due.before:x"],
             ModExpectsDateValue("due.before:x".to_owned()),
         ),
+        (
+            vec!["due.between:.."],
+            ModExpectsDateValue("due.between:..".to_owned()),
+        ),
+        (
+            vec!["due.between:2001"],
+            ModExpectsDateValue("due.between:2001".to_owned()),
+        ),
+        (
+            vec!["due.between:x..2001"],
+            ModExpectsDateValue("due.between:x..2001".to_owned()),
+        ),
+        (
+            vec!["issue.gt:x"],
+            ModExpectsNumberValue("issue.gt:x".to_owned()),
+        ),
+        (
+            vec!["issue.lte:inf"],
+            ModExpectsNumberValue("issue.lte:inf".to_owned()),
+        ),
+        (
+            vec!["issue.range:5"],
+            ModExpectsNumberValue("issue.range:5".to_owned()),
+        ),
+        (
+            vec!["issue.range:1-x"],
+            ModExpectsNumberValue("issue.range:1-x".to_owned()),
+        ),
+        (
+            vec!["pri.gt:1"],
+            ModExpectsNumberKey("pri.gt:1".to_owned()),
+        ),
+        (
+            vec!["due.lt:1"],
+            ModExpectsNumberKey("due.lt:1".to_owned()),
+        ),
+        (
+            vec!["ticket.regex:(OPS"],
+            ModExpectsRegexValue("ticket.regex:(OPS".to_owned()),
+        ),
+        (
+            vec!["pri.has:A"],
+            InvalidMod("pri.has:A".to_owned()),
+        ),
+        (
+            vec!["pri.between:2001..2002"],
+            ModExpectsDateKey("pri.between:2001..2002".to_owned()),
+        ),
+        (
+            vec!["issue.between:2001..2002"],
+            ModExpectsDateKey("issue.between:2001..2002".to_owned()),
+        ),
         (
             vec!["end.after:x"],
             ModExpectsDateValue("end.after:x".to_owned()),
@@ -695,6 +833,10 @@ fn invalid() -> Result<()> {
             vec!["/[foo/"],
             InvalidRegex("/[foo/".to_owned()),
         ),
+        (
+            vec!["/usr/lib"],
+            NotAFilterOrCommand("/usr/lib".to_owned()),
+        ),
     ] {
         let config = Config {
             now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
@@ -720,6 +862,398 @@ fn invalid() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn numeric() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["estimate.gt:2"],
+            concat!(
+                "1 a estimate:3\n",
+                "2 b estimate:2.5\n",
+            ),
+        ),
+        (
+            vec!["estimate.gte:2"],
+            concat!(
+                "1 a estimate:3\n",
+                "2 b estimate:2.5\n",
+                "3 c estimate:2\n",
+            ),
+        ),
+        (
+            vec!["estimate.lt:2"],
+            "4 d estimate:-1\n",
+        ),
+        (
+            vec!["estimate.lte:2.0"],
+            concat!(
+                "3 c estimate:2\n",
+                "4 d estimate:-1\n",
+            ),
+        ),
+        (
+            vec!["estimate.range:2-2.5"],
+            concat!(
+                "2 b estimate:2.5\n",
+                "3 c estimate:2\n",
+            ),
+        ),
+        (
+            vec!["estimate.range:-5--1"],
+            "4 d estimate:-1\n",
+        ),
+        (
+            vec!["estimate.range:3-2"],
+            concat!(
+                "1 a estimate:3\n",
+                "2 b estimate:2.5\n",
+                "3 c estimate:2\n",
+            ),
+        ),
+        (
+            // non-numeric and missing values never match, so only show up when inverted
+            vec!["-estimate.gt:-100"],
+            concat!(
+                "5 e estimate:lots\n",
+                "6 f\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a estimate:3\n",
+                    "b estimate:2.5\n",
+                    "c estimate:2\n",
+                    "d estimate:-1\n",
+                    "e estimate:lots\n",
+                    "f\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn strings() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["url.has:github"],
+            concat!(
+                "1 a url:https://github.com/foo ticket:OPS-12\n",
+                "2 b url:https://gitlab.com/github ticket:DEV-3\n",
+            ),
+        ),
+        (
+            vec!["url.startswith:https://github"],
+            "1 a url:https://github.com/foo ticket:OPS-12\n",
+        ),
+        (
+            vec!["ticket.startswith:OPS-"],
+            concat!(
+                "1 a url:https://github.com/foo ticket:OPS-12\n",
+                "3 c github ticket:OPS-7x\n",
+            ),
+        ),
+        (
+            vec!["ticket.endswith:x"],
+            "3 c github ticket:OPS-7x\n",
+        ),
+        (
+            vec!["ticket.regex:^OPS-[0-9]+$"],
+            "1 a url:https://github.com/foo ticket:OPS-12\n",
+        ),
+        (
+            vec!["end.startswith:2001-02"],
+            "4 x 2001-02-03 d\n",
+        ),
+        (
+            vec!["-url.has:github"],
+            concat!(
+                "3 c github ticket:OPS-7x\n",
+                "4 x 2001-02-03 d\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a url:https://github.com/foo ticket:OPS-12\n",
+                    "b url:https://gitlab.com/github ticket:DEV-3\n",
+                    "c github ticket:OPS-7x\n",
+                    "x 2001-02-03 d\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn hierarchy() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["+work"],
+            concat!(
+                "1 a +work.infra.k8s @office\n",
+                "3 c +work.docs @office.desk\n",
+                "4 d +work\n",
+            ),
+        ),
+        (
+            vec!["+work.infra"],
+            "1 a +work.infra.k8s @office\n",
+        ),
+        (
+            vec!["-+work"],
+            "2 b +work-x +home\n",
+        ),
+        (
+            vec!["@office"],
+            concat!(
+                "1 a +work.infra.k8s @office\n",
+                "3 c +work.docs @office.desk\n",
+            ),
+        ),
+        (
+            vec!["@office.desk"],
+            "3 c +work.docs @office.desk\n",
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a +work.infra.k8s @office\n",
+                    "b +work-x +home\n",
+                    "c +work.docs @office.desk\n",
+                    "d +work\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn search() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["/milk/"],
+            concat!(
+                "1 a Buy MILK +shop | milk the cow\n",
+                "2 b sell cheese @milkbar\n",
+            ),
+        ),
+        (
+            vec!["/Milk/"],
+            "",
+        ),
+        (
+            vec!["/Milk/i"],
+            concat!(
+                "1 a Buy MILK +shop | milk the cow\n",
+                "2 b sell cheese @milkbar\n",
+            ),
+        ),
+        (
+            vec!["/milk/ib"],
+            "1 a Buy MILK +shop | milk the cow\n",
+        ),
+        (
+            vec!["/milk/b"],
+            "",
+        ),
+        (
+            vec!["/^a buy milk$/ib"],
+            "1 a Buy MILK +shop | milk the cow\n",
+        ),
+        (
+            vec!["/cheese/ia"],
+            "3 c 1+1 math | Cheese notes\n",
+        ),
+        (
+            vec!["/1+1/"],
+            "",
+        ),
+        (
+            vec!["/1+1/l"],
+            "3 c 1+1 math | Cheese notes\n",
+        ),
+        (
+            vec!["-/cheese/ia"],
+            concat!(
+                "1 a Buy MILK +shop | milk the cow\n",
+                "2 b sell cheese @milkbar\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a Buy MILK +shop | milk the cow\n",
+                    "b sell cheese @milkbar\n",
+                    "c 1+1 math | Cheese notes\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn annotations() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["annotation.any:"],
+            concat!(
+                "1 a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+                "2 b buy milk | from the corner shop\n",
+            ),
+        ),
+        (
+            vec!["annotation.none:"],
+            "3 c plain task\n",
+        ),
+        (
+            vec!["annotation:from the corner shop"],
+            "2 b buy milk | from the corner shop\n",
+        ),
+        (
+            vec!["annotation.has:answer"],
+            "1 a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+        ),
+        (
+            vec!["annotation.after:2001-01-31"],
+            "1 a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+        ),
+        (
+            vec!["annotation.before:2001-01"],
+            "",
+        ),
+        (
+            vec!["annotation.in:2001-01"],
+            "1 a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+        ),
+        (
+            vec!["annotation.between:yesterday..today"],
+            "1 a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+        ),
+        (
+            vec!["-annotation.after:2001-01-31"],
+            concat!(
+                "2 b buy milk | from the corner shop\n",
+                "3 c plain task\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a call bob | 2001-01-15 left a message | 2001-02-02 no answer\n",
+                    "b buy milk | from the corner shop\n",
+                    "c plain task\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // The `annotation` key is reserved for annotations, so a pair written with it is only found
+    // by a regex.
+    for (args, expect) in &[
+        (vec!["annotation:urgent"], "2 b buy milk | urgent\n"),
+        (vec!["/annotation:urgent/"], "1 a call bob annotation:urgent\n"),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some("a call bob annotation:urgent\nb buy milk | urgent\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for (arg, expect) in &[
+        (
+            "annotation.after:someday",
+            ModExpectsDateValue("annotation.after:someday".to_owned()),
+        ),
+        (
+            "annotation.gt:1",
+            ModExpectsNumberKey("annotation.gt:1".to_owned()),
+        ),
+        (
+            "annotation.foo:bar",
+            InvalidMod("annotation.foo:bar".to_owned()),
+        ),
+    ] {
+        let config = Config {
+            args: vec![arg.to_string()],
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(format!("{:?}", actual), format!("{:?}", expect));
+    }
+    Ok(())
+}
+
 #[test]
 fn defaults() -> Result<()> {
     for (defaults, expect) in &[
@@ -764,3 +1298,269 @@ fn defaults() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn timezone() -> Result<()> {
+    for (args, utc_offset, timezone, expect) in &[
+        (
+            vec!["due.before:now"],
+            0,
+            Some("+01:00\n"),
+            concat!(
+                "1 a due:2001-02-03T04:00Z\n",
+                "2 b due:2001-02-03T04:30+01:00\n",
+                "5 e due:2001-02-03T04\n",
+            ),
+        ),
+        (
+            vec!["due.after:now"],
+            0,
+            Some("+01:00\n"),
+            "3 c due:2001-02-03T04:30-01:00\n",
+        ),
+        (
+            vec!["due.in:today"],
+            0,
+            Some("+01:00\n"),
+            concat!(
+                "1 a due:2001-02-03T04:00Z\n",
+                "2 b due:2001-02-03T04:30+01:00\n",
+                "3 c due:2001-02-03T04:30-01:00\n",
+                "4 d due:2001-02-03T05\n",
+                "5 e due:2001-02-03T04\n",
+            ),
+        ),
+        (
+            vec!["due.before:2001-02-03T04:15Z"],
+            0,
+            Some("+01:00\n"),
+            concat!(
+                "1 a due:2001-02-03T04:00Z\n",
+                "2 b due:2001-02-03T04:30+01:00\n",
+                "5 e due:2001-02-03T04\n",
+            ),
+        ),
+        (
+            // without a configured zone, now is the local time at the system's offset
+            vec!["due.before:now"],
+            -5,
+            None,
+            concat!(
+                "1 a due:2001-02-03T04:00Z\n",
+                "2 b due:2001-02-03T04:30+01:00\n",
+                "3 c due:2001-02-03T04:30-01:00\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            utc_offset: chrono::FixedOffset::east(utc_offset * 3600),
+            timezone: timezone.map(|tz| tz.to_owned()),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a due:2001-02-03T04:00Z\n",
+                    "b due:2001-02-03T04:30+01:00\n",
+                    "c due:2001-02-03T04:30-01:00\n",
+                    "d due:2001-02-03T05\n",
+                    "e due:2001-02-03T04\n",
+                )
+                .to_owned(),
+            ),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    let config = Config {
+        timezone: Some("Europe/Berlin".to_owned()),
+        ..Default::default()
+    };
+    match chore::run(config) {
+        Err(e) => assert_eq!(
+            format!("{:?}", e),
+            format!("{:?}", InvalidTimezone("Europe/Berlin".to_owned()))
+        ),
+        Ok(_) => panic!("expected error"),
+    }
+    Ok(())
+}
+
+#[test]
+fn dependencies() -> Result<()> {
+    for (args, expect) in &[
+        (
+            vec!["+blocked"],
+            concat!(
+                "3 deploy id:deploy depends:review,test\n",
+                "5 write docs depends:deploy\n",
+            ),
+        ),
+        (
+            vec!["+ready"],
+            concat!(
+                "2 announce depends:design,gone\n",
+                "4 review id:review\n",
+            ),
+        ),
+        (
+            vec!["-+blocked"],
+            concat!(
+                "1 x design id:design\n",
+                "2 announce depends:design,gone\n",
+                "4 review id:review\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "x design id:design\n",
+                    "announce depends:design,gone\n",
+                    "deploy id:deploy depends:review,test\n",
+                    "review id:review\n",
+                    "write docs depends:deploy\n",
+                )
+                .to_owned(),
+            ),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // Bare `blocked` and `ready` are left to aliases and reports of those names.
+    let config = Config {
+        args: vec!["+blocked".to_owned(), "ready".to_owned()],
+        tasks: Some("deploy id:deploy depends:review\nreview id:review\n".to_owned()),
+        command_aliases: vec![File {
+            name: "ready".to_owned(),
+            content: "modify +ready".to_owned(),
+        }],
+        ..Default::default()
+    };
+    match chore::run(config)? {
+        Output::WriteFiles { tasks, .. } => assert_eq!(
+            &tasks,
+            "deploy id:deploy depends:review +ready\nreview id:review\n"
+        ),
+        _ => panic!("expected WriteFiles"),
+    }
+    Ok(())
+}
+
+#[test]
+fn virtual_tags() -> Result<()> {
+    for (args, due_soon, expect) in &[
+        (vec!["+overdue"], None, "1 a due:2001-02-01\n"),
+        (vec!["+today"], None, "2 b due:2001-02-03\n"),
+        (vec!["+tomorrow"], None, "3 c due:2001-02-04\n"),
+        (
+            vec!["+week"],
+            None,
+            concat!(
+                "1 a due:2001-02-01\n",
+                "2 b due:2001-02-03\n",
+                "3 c due:2001-02-04\n",
+            ),
+        ),
+        (
+            vec!["+soon"],
+            None,
+            concat!(
+                "2 b due:2001-02-03\n",
+                "3 c due:2001-02-04\n",
+            ),
+        ),
+        (
+            vec!["+soon"],
+            Some("30d"),
+            concat!(
+                "2 b due:2001-02-03\n",
+                "3 c due:2001-02-04\n",
+                "4 d due:2001-02-20\n",
+            ),
+        ),
+        (
+            vec!["+waiting"],
+            None,
+            concat!(
+                "6 f wait:2001-03-01 recur:1w\n",
+                "9 call bob +waiting\n",
+            ),
+        ),
+        (vec!["+annotated"], None, "7 g id:g | note\n"),
+        (vec!["+recurring"], None, "6 f wait:2001-03-01 recur:1w\n"),
+
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(
+                concat!(
+                    "a due:2001-02-01\n",
+                    "b due:2001-02-03\n",
+                    "c due:2001-02-04\n",
+                    "d due:2001-02-20\n",
+                    "x e due:2001-01-01\n",
+                    "f wait:2001-03-01 recur:1w\n",
+                    "g id:g | note\n",
+                    "h depends:g\n",
+                    "call bob +waiting\n",
+                )
+                .to_owned(),
+            ),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            due_soon: due_soon.map(|s| s.to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => {
+                assert_eq!(&stdout, expect)
+            }
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    // Without `due` as a date key, the due date tags never apply.
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["+overdue".to_owned()],
+        tasks: Some("a due:2001-02-01\n".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(
+        chore::run(config)?,
+        Output::JustPrint {
+            stdout: "".to_owned()
+        }
+    );
+
+    let config = Config {
+        due_soon: Some("whenever\n".to_owned()),
+        ..Default::default()
+    };
+    let actual = match chore::run(config) {
+        Ok(_) => panic!("expected error"),
+        Err(e) => e,
+    };
+    assert_eq!(
+        format!("{:?}", actual),
+        format!("{:?}", InvalidDueSoon("whenever".to_owned()))
+    );
+    Ok(())
+}
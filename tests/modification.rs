@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use chore::*;
 
 #[test]
@@ -168,6 +170,36 @@ fn general() -> Result<()> {
                 "x 2001-02-03 (H) 2001-01-02 @work issue:123 due:2009-09-09\n",
             ),
         ),
+        (
+            vec!["2", "modify", "due:next-friday", "scheduled:eom"],
+            concat!(
+                "DEL add task due:2002-03-04T05:06:07\n",
+                "ADD add task due:2001-02-09 scheduled:2001-02-28\n",
+            ),
+            false,
+            concat!(
+                "(M) 2001-02-03 @home +chore add tests\n",
+                "add task due:2001-02-09 scheduled:2001-02-28\n",
+                "x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+            ),
+        ),
+        (
+            vec!["modify", "wait:due-3d", "scheduled:entry+1w", "until:fri+2d"],
+            concat!(
+                "DEL (M) 2001-02-03 @home +chore add tests\n",
+                "ADD (M) 2001-02-03 @home +chore add tests scheduled:2001-02-10 until:2001-02-11\n",
+                "DEL add task due:2002-03-04T05:06:07\n",
+                "ADD add task due:2002-03-04T05:06:07 wait:2002-03-01T05:06:07 until:2001-02-11\n",
+                "DEL x 2001-02-03 (H) 2001-01-02 @work issue:123\n",
+                "ADD x 2001-02-03 (H) 2001-01-02 @work issue:123 scheduled:2001-01-09 until:2001-02-11\n",
+            ),
+            true,
+            concat!(
+                "(M) 2001-02-03 @home +chore add tests scheduled:2001-02-10 until:2001-02-11\n",
+                "add task due:2002-03-04T05:06:07 wait:2002-03-01T05:06:07 until:2001-02-11\n",
+                "x 2001-02-03 (H) 2001-01-02 @work issue:123 scheduled:2001-01-09 until:2001-02-11\n",
+            ),
+        ),
         (
             vec!["modify", "end:"],
             concat!(
@@ -638,6 +670,14 @@ fn invalid() -> Result<()> {
             vec!["modify", "end:x"],
             KeyExpectsDateValue("end:x".to_owned()),
         ),
+        (
+            vec!["modify", "end:due-3h"],
+            InvalidEnd("end:due-3h".to_owned()),
+        ),
+        (
+            vec!["modify", "wait:foo-3d"],
+            KeyExpectsDateValue("wait:foo-3d".to_owned()),
+        ),
         (vec!["modify", "pri:x"], InvalidPriority("pri:x".to_owned())),
         (
             vec!["modify", "entry:2001-02-03T04:05:06"],
@@ -675,3 +715,249 @@ fn invalid() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn timezone() -> Result<()> {
+    for (args, expect_tasks) in &[
+        (
+            vec!["modify", "due:2001-02-03T10:00Z"],
+            "add task due:2001-02-03T11:00+01:00\n",
+        ),
+        (
+            vec!["modify", "due:2001-02-03T10"],
+            "add task due:2001-02-03T10+0100\n",
+        ),
+        (
+            vec!["modify", "due:tomorrow"],
+            "add task due:2001-02-04\n",
+        ),
+        (
+            vec!["modify", "due:06:00"],
+            "add task due:2001-02-03T06:00+01:00\n",
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            utc_offset: chrono::FixedOffset::east(0),
+            timezone: Some("+01:00\n".to_owned()),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some("add task\n".to_owned()),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::WriteFiles { tasks, .. } => assert_eq!(&tasks, expect_tasks),
+            _ => panic!("expected WriteFiles"),
+        }
+    }
+
+    // Without a configured timezone, values are written as entered, whatever the local offset.
+    for (args, expect_tasks) in &[
+        (
+            vec!["modify", "due:2021-03-05T09"],
+            "add task due:2021-03-05T09\n",
+        ),
+        (
+            vec!["modify", "due:14:30"],
+            "add task due:2001-02-03T14:30\n",
+        ),
+        (
+            vec!["modify", "due:2001-02-03T10:00Z"],
+            "add task due:2001-02-03T10:00Z\n",
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            utc_offset: chrono::FixedOffset::west(4 * 3600),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some("add task\n".to_owned()),
+            date_keys: Some("due:\n".to_owned()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::WriteFiles { tasks, .. } => assert_eq!(&tasks, expect_tasks),
+            _ => panic!("expected WriteFiles"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn holidays() -> Result<()> {
+    for (args, tasks, weekend, expect_tasks) in &[
+        (
+            vec!["modify", "due:3W"],
+            "add task\n",
+            None,
+            "add task due:2001-02-12T04:05:06\n",
+        ),
+        (
+            vec!["modify", "due:3W"],
+            "add task\n",
+            Some("fri\nsat\n"),
+            "add task due:2001-02-11T04:05:06\n",
+        ),
+        (
+            vec!["modify", "due:3W"],
+            "add task\n",
+            Some("sat sun sat sun sat sun sat\n"),
+            "add task due:2001-02-12T04:05:06\n",
+        ),
+        (
+            vec!["modify", "due:20W"],
+            "add task\n",
+            None,
+            "add task due:2001-03-08T04:05:06\n",
+        ),
+        (
+            vec!["modify", "due:2001-03-08-20W"],
+            "add task\n",
+            None,
+            "add task due:2001-02-02\n",
+        ),
+        (
+            vec!["modify", "due:2001-02-09-2W"],
+            "add task\n",
+            None,
+            "add task due:2001-02-02\n",
+        ),
+        (
+            vec!["modify", "+done", "end:today"],
+            "(M) 2001-02-13 recur:1W add tests\n",
+            None,
+            concat!(
+                "(M) 2001-02-15 recur:1W add tests\n",
+                "x 2001-02-03 (M) 2001-02-13 recur:1W add tests\n",
+            ),
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            utc_offset: chrono::FixedOffset::east(0),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
+            weekend: weekend.map(|s| s.to_owned()),
+            holidays: vec![File {
+                name: "office".to_owned(),
+                content: concat!(
+                    "# closures\n",
+                    "2001-02-05\n",
+                    "2001-02-07..2001-02-08\n",
+                    "\n",
+                    "02-14 # every year\n",
+                )
+                .to_owned(),
+            }],
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::WriteFiles { tasks, .. } => assert_eq!(&tasks, expect_tasks),
+            _ => panic!("expected WriteFiles"),
+        }
+    }
+
+    for (weekend, holiday, expect) in &[
+        (None, "2001-13-01", InvalidHoliday("2001-13-01".to_owned())),
+        (None, "christmas", InvalidHoliday("christmas".to_owned())),
+        (Some("sat caturday"), "", InvalidWeekday("caturday".to_owned())),
+        (
+            Some("mon tue wed thu fri sat sun"),
+            "",
+            InvalidWeekend("mon tue wed thu fri sat sun".to_owned()),
+        ),
+        (
+            Some("sat sun mon tue wed thu fri fri"),
+            "",
+            InvalidWeekend("sat sun mon tue wed thu fri fri".to_owned()),
+        ),
+    ] {
+        let config = Config {
+            args: vec!["list".to_owned()],
+            weekend: weekend.map(|s| s.to_owned()),
+            holidays: vec![File {
+                name: "office".to_owned(),
+                content: holiday.to_string(),
+            }],
+            ..Default::default()
+        };
+        match chore::run(config) {
+            Err(e) => assert_eq!(format!("{:?}", e), format!("{:?}", expect)),
+            Ok(_) => panic!("expected error"),
+        }
+    }
+
+    // Offsets out of reach, whether too large or with holidays covering every day
+    for (arg, holiday) in &[("due:99999999W", ""), ("due:3W", "2000-01-01..2200-12-31")] {
+        let config = Config {
+            args: vec!["modify".to_owned(), arg.to_string()],
+            tasks: Some("add task\n".to_owned()),
+            date_keys: Some("due:\n".to_owned()),
+            holidays: vec![File {
+                name: "office".to_owned(),
+                content: holiday.to_string(),
+            }],
+            ..Default::default()
+        };
+        match chore::run(config) {
+            Err(e) => assert_eq!(
+                format!("{:?}", e),
+                format!("{:?}", KeyExpectsDateValue(arg.to_string()))
+            ),
+            Ok(_) => panic!("expected error"),
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn annotations() -> Result<()> {
+    for (args, tasks, expect_tasks) in &[
+        (
+            vec!["modify", ">>|", "left", "a", "message"],
+            "call bob\n",
+            "call bob | 2001-02-03 left a message\n",
+        ),
+        (
+            vec!["modify", ">>|", "no", "answer"],
+            "call bob | 2001-01-15 left a message\n",
+            "call bob | 2001-01-15 left a message | 2001-02-03 no answer\n",
+        ),
+        (
+            vec!["add", "call", "bob", "|", "about", "lunch"],
+            "",
+            "call bob | 2001-02-03 about lunch\n",
+        ),
+        (
+            vec!["modify", ">>| left a message"],
+            "call bob\n",
+            "call bob | 2001-02-03 left a message\n",
+        ),
+        (
+            vec!["modify", ">>|left", "a", "message"],
+            "call bob\n",
+            "call bob | 2001-02-03 left a message\n",
+        ),
+        (
+            vec!["modify", ">>not|an", "annotation"],
+            "call bob\n",
+            "call bob not|an annotation\n",
+        ),
+    ] {
+        let config = Config {
+            now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_string()),
+            ..Default::default()
+        };
+
+        match chore::run(config)? {
+            Output::WriteFiles { tasks, .. } => assert_eq!(&tasks, expect_tasks),
+            _ => panic!("expected WriteFiles"),
+        }
+    }
+    Ok(())
+}
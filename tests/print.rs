@@ -1,3 +1,5 @@
+#![allow(clippy::useless_concat)]
+
 use chore::*;
 use regex::Regex;
 
@@ -46,7 +48,7 @@ fn general() -> Result<()> {
             args: args.iter().map(|s| s.to_string()).collect(),
             tasks: Some(tasks.to_string()),
             date_keys: Some("due:\nscheduled:\nwait:\nuntil:\n".to_owned()),
-            print_color: true,
+            is_terminal: true,
             ..Default::default()
         };
 
@@ -63,3 +65,165 @@ fn general() -> Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn theme() -> Result<()> {
+    let theme = concat!(
+        "# colours of parts\n",
+        "project 33\n",
+        "context yellow\n",
+        "\n",
+        "(A) white on 196\n",
+        "(B) 21\n",
+        "rule +overdue = red\n",
+        "rule +home due.after:today = 240\n",
+    );
+    let tasks = concat!(
+        "(A) pay rent due:2001-02-01 +home\n",
+        "(B) call mum @phone +home due:2001-03-01\n",
+        "(C) fix bike +home @garage\n",
+    );
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["list".to_owned()],
+        tasks: Some(tasks.to_owned()),
+        date_keys: Some("due:\n".to_owned()),
+        theme: Some(theme.to_owned()),
+        is_terminal: true,
+        ..Default::default()
+    };
+    let expect = concat!(
+        "\x1b[38;5;246m1 \x1b[48;5;196m\x1b[38;5;255m(A)\x1b[0m ",
+        "\x1b[38;5;196mpay rent due:2001-02-01 +home\n",
+        "\x1b[38;5;246m2 \x1b[48;5;21m\x1b[38;5;0m(B)\x1b[0m ",
+        "\x1b[38;5;240mcall mum @phone +home due:2001-03-01\n",
+        "\x1b[38;5;246m3 \x1b[48;5;9m\x1b[38;5;0m(C)\x1b[0m ",
+        "\x1b[38;5;255mfix bike \x1b[38;5;33m+home \x1b[38;5;11m@garage\n",
+        "\x1b[0m",
+    );
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+        _ => panic!("expected JustPrint"),
+    }
+
+    // Table cells take the colours of the parts and of the rule matching their task.
+    let config = Config {
+        now: chrono::NaiveDate::from_ymd(2001, 2, 3).and_hms(4, 5, 6),
+        args: vec!["list".to_owned(), "columns:nr,projects,body".to_owned()],
+        tasks: Some(tasks.to_owned()),
+        date_keys: Some("due:\n".to_owned()),
+        theme: Some(theme.to_owned()),
+        is_terminal: true,
+        ..Default::default()
+    };
+    let expect = concat!(
+        "nr  projects  body\n",
+        " \x1b[38;5;196m1  +home     pay rent\x1b[0m\n",
+        " \x1b[38;5;240m2  +home     call mum\x1b[0m\n",
+        " \x1b[38;5;246m3  \x1b[38;5;33m+home     \x1b[38;5;255mfix bike\x1b[0m\n",
+    );
+    match chore::run(config)? {
+        Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+        _ => panic!("expected JustPrint"),
+    }
+
+    for line in &[
+        "project",
+        "project 256",
+        "bogus red",
+        "(A) on nope",
+        "(A) red on",
+        "rule +home red",
+        "rule = red",
+        "rule notafilter = red",
+    ] {
+        let config = Config {
+            args: vec!["list".to_owned()],
+            theme: Some(format!("{}\n", line)),
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            format!("{:?}", actual),
+            format!("{:?}", InvalidTheme(line.to_string()))
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn color() -> Result<()> {
+    let tasks = "(A) pay rent +home\n";
+    for (args, is_terminal, env, expect) in &[
+        (vec!["list"], false, ("", false, false, ""), "1 (A) pay rent +home\n"),
+        (
+            vec!["--color=always", "list"],
+            false,
+            ("", false, false, ""),
+            concat!(
+                "\x1b[38;5;246m1 \x1b[48;5;1m\x1b[38;5;255m(A)\x1b[0m ",
+                "pay rent \x1b[38;5;23m+home\n",
+                "\x1b[0m",
+            ),
+        ),
+        (vec!["--color=never"], true, ("", false, false, ""), "1 (A) pay rent +home\n"),
+        (vec!["list"], true, ("", true, false, ""), "1 (A) pay rent +home\n"),
+        (vec!["list"], true, ("dumb", false, false, ""), "1 (A) pay rent +home\n"),
+        (
+            vec!["--color-depth=16"],
+            false,
+            ("dumb", false, true, ""),
+            concat!(
+                "\x1b[90m1 \x1b[41m\x1b[37m(A)\x1b[0m ",
+                "pay rent \x1b[30m+home\n",
+                "\x1b[0m",
+            ),
+        ),
+        (
+            vec!["list"],
+            true,
+            ("xterm", false, false, "truecolor"),
+            concat!(
+                "\x1b[38;2;148;148;148m1 \x1b[48;2;205;0;0m\x1b[38;2;238;238;238m(A)\x1b[0m ",
+                "pay rent \x1b[38;2;18;52;86m+home\n",
+                "\x1b[0m",
+            ),
+        ),
+    ] {
+        let (term, no_color, clicolor_force, colorterm) = env;
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_owned()),
+            is_terminal: *is_terminal,
+            no_color: *no_color,
+            clicolor_force: *clicolor_force,
+            term: Some(term.to_string()),
+            colorterm: Some(colorterm.to_string()),
+            theme: Some("project #123456\n".to_owned()),
+            ..Default::default()
+        };
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+            _ => panic!("expected JustPrint"),
+        }
+    }
+
+    for arg in &["--color=sometimes", "--color-depth=8"] {
+        let config = Config {
+            args: vec![arg.to_string()],
+            ..Default::default()
+        };
+        let actual = match chore::run(config) {
+            Ok(_) => panic!("expected error"),
+            Err(e) => e,
+        };
+        assert_eq!(
+            format!("{:?}", actual),
+            format!("{:?}", InvalidOption(arg.to_string()))
+        );
+    }
+    Ok(())
+}
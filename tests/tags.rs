@@ -0,0 +1,36 @@
+#![allow(clippy::useless_concat)]
+
+use chore::*;
+
+#[test]
+fn blocked() -> Result<()> {
+    let tasks = concat!(
+        "a id:a\n",
+        "b depends:a\n",
+        "c depends:a,b\n",
+        "x d id:d\n",
+        "e depends:d\n",
+    );
+    for (args, expect) in &[
+        (vec!["tags"], concat!("+blocked 2\n", "+ready 2\n")),
+        (
+            vec!["+blocked"],
+            concat!("2 b depends:a\n", "3 c depends:a,b\n"),
+        ),
+        (
+            vec!["-+blocked"],
+            concat!("1 a id:a\n", "4 x d id:d\n", "5 e depends:d\n"),
+        ),
+    ] {
+        let config = Config {
+            args: args.iter().map(|s| s.to_string()).collect(),
+            tasks: Some(tasks.to_owned()),
+            ..Default::default()
+        };
+        match chore::run(config)? {
+            Output::JustPrint { stdout } => assert_eq!(&stdout, expect),
+            _ => panic!("expected JustPrint"),
+        }
+    }
+    Ok(())
+}